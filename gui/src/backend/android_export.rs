@@ -0,0 +1,628 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+use crate::backend::android_xml::{self, decode_ssid, mac_to_android, metered_to_android, unquote};
+use crate::backend::config::Config;
+use crate::backend::keyfile::KeyFile;
+use crate::backend::nmconnection;
+use crate::models::network::{Metered, NetworkSettings, Security};
+
+/// WifiConfigStore.xml with the local networks merged in, and what changed
+#[derive(Debug, Clone, Default)]
+pub struct MergeResult {
+    pub xml: String,
+    /// SSIDs that got a new `<Network>` block
+    pub added: Vec<String>,
+    /// SSIDs whose block was changed, and what was changed
+    pub updated: Vec<(String, String)>,
+    /// Local networks that cannot be written to the store, and why
+    pub skipped: Vec<(String, String)>,
+}
+
+impl MergeResult {
+    pub fn has_changes(&self) -> bool {
+        !self.added.is_empty() || !self.updated.is_empty()
+    }
+}
+
+/// A local profile to merge into the store
+#[derive(Debug, Clone)]
+pub struct LocalNetwork {
+    pub settings: NetworkSettings,
+    /// `autoconnect-priority`, `None` when the keyfile does not set it
+    pub priority: Option<i32>,
+}
+
+impl LocalNetwork {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let priority = KeyFile::load(path)?
+            .get("connection", "autoconnect-priority")
+            .and_then(|p| p.parse().ok());
+        Ok(Self { settings: nmconnection::load_settings(path)?, priority })
+    }
+}
+
+/// A local network waiting for its `<Network>` block, or to be added
+struct Pending<'a> {
+    network: &'a LocalNetwork,
+    key_type: &'static str,
+    /// Why a device block with the same SSID was left alone
+    conflict: Option<String>,
+}
+
+/// Merge the local connection files into the pulled WifiConfigStore.xml
+pub fn preview(config: &Config) -> Result<MergeResult, String> {
+    let store = fs::read_to_string(&config.xml_file).map_err(|e| match e.kind() {
+        ErrorKind::NotFound => format!("{} not found; pull it from the device first", config.xml_file.display()),
+        _ => format!("Failed to read {}: {}", config.xml_file.display(), e),
+    })?;
+    let networks = nmconnection::load_networks(&config.local_dir)
+        .into_iter()
+        .filter_map(|network| network.path)
+        .map(|path| LocalNetwork::load(&path))
+        .collect::<Result<Vec<_>, String>>()?;
+    merge(&store, &networks)
+}
+
+/// Replace the store with the merged XML, keeping the previous one as `.bak`
+pub fn write(config: &Config, xml: &str) -> Result<(), String> {
+    let backup = config.xml_file.with_extension("xml.bak");
+    fs::copy(&config.xml_file, &backup)
+        .map_err(|e| format!("Failed to back up {}: {}", config.xml_file.display(), e))?;
+
+    let tmp = config.xml_file.with_extension("xml.tmp");
+    fs::write(&tmp, xml).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    fs::rename(&tmp, &config.xml_file)
+        .map_err(|e| format!("Failed to write {}: {}", config.xml_file.display(), e))
+}
+
+/// Add the networks the store does not have, and update changed keys and the
+/// hidden, priority, metered and MAC settings the local keyfiles set. Everything
+/// else, including device-only networks and elements this parser does not know,
+/// is copied unchanged.
+pub fn merge(store: &str, networks: &[LocalNetwork]) -> Result<MergeResult, String> {
+    let mut result = MergeResult::default();
+    let mut pending: Vec<Pending> = Vec::new();
+    for network in networks {
+        match writable(&network.settings) {
+            Ok(key_type) => pending.push(Pending { network, key_type, conflict: None }),
+            Err(reason) => result.skipped.push((network.settings.ssid.clone(), reason)),
+        }
+    }
+
+    let mut reader = Reader::from_str(store);
+    let mut writer = Writer::new(Vec::new());
+    let mut found_list = false;
+    let mut in_list = false;
+
+    loop {
+        let event = reader.read_event().map_err(|e| xml_error(&reader, e))?;
+        match &event {
+            Event::Start(e) if e.name().as_ref() == b"NetworkList" => {
+                found_list = true;
+                in_list = true;
+            }
+            Event::Start(e) if in_list && e.name().as_ref() == b"Network" => {
+                let mut block = vec![event];
+                loop {
+                    let event = reader.read_event().map_err(|e| xml_error(&reader, e))?;
+                    let end = matches!(&event, Event::End(e) if e.name().as_ref() == b"Network");
+                    if matches!(event, Event::Eof) {
+                        return Err("Unexpected end of XML inside <Network>".to_string());
+                    }
+                    block.push(event);
+                    if end {
+                        break;
+                    }
+                }
+                if let Some(idx) = update_block(&mut block, &mut pending, &mut result)? {
+                    pending.remove(idx);
+                }
+                for event in block {
+                    write_event(&mut writer, event)?;
+                }
+                continue;
+            }
+            Event::End(e) if e.name().as_ref() == b"NetworkList" => {
+                in_list = false;
+                add_blocks(&mut writer, &mut pending, &mut result);
+            }
+            Event::Empty(e) if e.name().as_ref() == b"NetworkList" => {
+                // Open up `<NetworkList />` so new networks can go inside
+                found_list = true;
+                write_event(&mut writer, Event::Start(BytesStart::new("NetworkList")))?;
+                writer.get_mut().push(b'\n');
+                add_blocks(&mut writer, &mut pending, &mut result);
+                write_event(&mut writer, Event::End(BytesEnd::new("NetworkList")))?;
+                continue;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        write_event(&mut writer, event)?;
+    }
+
+    if !found_list {
+        return Err("WifiConfigStore.xml has no <NetworkList>; pull it from the device first".to_string());
+    }
+
+    result.xml = String::from_utf8(writer.into_inner())
+        .map_err(|e| format!("Merged XML is not UTF-8: {}", e))?;
+    // The phone rejects a store it cannot parse, so check before offering it
+    android_xml::parse_str(&result.xml)?;
+    Ok(result)
+}
+
+/// Android key management type of a local network, as at the end of `ConfigKey`
+fn writable(network: &NetworkSettings) -> Result<&'static str, String> {
+    let key_type = match network.security {
+        Security::Open => "NONE",
+        Security::Owe => "OWE",
+        Security::Wep => "WEP",
+        Security::WpaPsk | Security::Transition => "WPA_PSK",
+        Security::Sae => "SAE",
+        Security::Enterprise => return Err("enterprise networks are not written to the store".to_string()),
+        Security::Unknown(ref v) => return Err(format!("unknown security {}", v)),
+    };
+    if network.security.requires_password() && network.password.is_empty() {
+        return Err("no password".to_string());
+    }
+    Ok(key_type)
+}
+
+/// `"SSID"KEYTYPE`, how Android identifies a saved network
+fn config_key(ssid: &str, key_type: &str) -> String {
+    format!("\"{}\"{}", ssid, key_type)
+}
+
+/// Passphrases are stored quoted, raw 64-digit hex keys as they are
+fn psk_value(password: &str) -> String {
+    if password.len() == 64 && password.bytes().all(|b| b.is_ascii_hexdigit()) {
+        password.to_string()
+    } else {
+        format!("\"{}\"", password)
+    }
+}
+
+/// ASCII WEP keys are stored quoted, 10/26-digit hex keys as they are
+fn wep_value(password: &str) -> String {
+    if matches!(password.len(), 10 | 26) && password.bytes().all(|b| b.is_ascii_hexdigit()) {
+        password.to_string()
+    } else {
+        format!("\"{}\"", password)
+    }
+}
+
+/// Whether a device entry stored as `device_type` is the same network as a
+/// local one written as `key_type`; transition mode may be stored as either
+fn same_key_type(security: &Security, key_type: &str, device_type: &str) -> bool {
+    key_type == device_type || (*security == Security::Transition && device_type == "SAE")
+}
+
+/// Match a `<Network>` block against the pending local networks by SSID and
+/// replace the key and the settings that differ from what the keyfile sets.
+/// A block with another key type is left alone and the network reported.
+fn update_block(
+    block: &mut Vec<Event<'_>>,
+    pending: &mut [Pending],
+    result: &mut MergeResult,
+) -> Result<Option<usize>, String> {
+    let Some(ssid) = find_string(block, "SSID")?.map(|(_, _, value)| decode_ssid(&value)) else {
+        return Ok(None);
+    };
+    let Some(idx) = pending.iter().position(|p| p.network.settings.ssid == ssid) else {
+        return Ok(None);
+    };
+    let (network, key_type) = (pending[idx].network, pending[idx].key_type);
+    let settings = &network.settings;
+
+    // The key type follows the quoted SSID in the ConfigKey
+    let config_key = find_string(block, "ConfigKey")?.map(|(_, _, value)| value);
+    if let Some((_, device_type)) = config_key.as_deref().and_then(|key| key.rsplit_once('"')) {
+        if !same_key_type(&settings.security, key_type, device_type) {
+            pending[idx].conflict = Some(format!(
+                "the device stores it as {}, locally it is {}; change the security on one side",
+                device_type, settings.security
+            ));
+            return Ok(None);
+        }
+    }
+
+    let mut changed = Vec::new();
+    match key_type {
+        "WPA_PSK" | "SAE" => {
+            if let Some((start, end, current)) = find_string(block, "PreSharedKey")? {
+                if unquote(&current) != settings.password {
+                    let text = Event::Text(BytesText::new(&psk_value(&settings.password)).into_owned());
+                    block.splice(start + 1..end, [text]);
+                    changed.push("password");
+                }
+            }
+        }
+        "WEP" => {
+            if let Some((idx, current)) = find_wep_key(block) {
+                if unquote(&current) != settings.password {
+                    let value = wep_value(&settings.password);
+                    block[idx] = Event::Empty(BytesStart::new("item").with_attributes([("value", value.as_str())]));
+                    changed.push("WEP key");
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let current = |block: &[Event<'_>], name: &str| find_value(block, name).map(|(_, value)| value);
+
+    // Every profile template writes hidden=false, so a missing key only gets
+    // added for hidden=true; an existing one is kept in line both ways
+    let hidden = current(block, "HiddenSSID");
+    if (settings.hidden || hidden.is_some()) && hidden.as_deref() != Some(&settings.hidden.to_string()) {
+        set_value(block, "boolean", "HiddenSSID", &settings.hidden.to_string());
+        changed.push("hidden");
+    }
+    if let Some(priority) = network.priority {
+        // Imported priorities were clamped to NetworkManager's range, so compare them clamped
+        let device: i64 = current(block, "Priority").and_then(|p| p.parse().ok()).unwrap_or(0);
+        if device.clamp(-999, 999) != i64::from(priority) {
+            set_value(block, "int", "Priority", &priority.to_string());
+            changed.push("priority");
+        }
+    }
+    if settings.metered != Metered::Unknown {
+        let metered = metered_to_android(settings.metered).to_string();
+        if current(block, "MeteredOverride").as_deref().unwrap_or("0") != metered {
+            set_value(block, "int", "MeteredOverride", &metered);
+            changed.push("metered");
+        }
+    }
+    if let Some(mac) = mac_to_android(&settings.mac_address) {
+        if current(block, "MacRandomizationSetting") != Some(mac.to_string()) {
+            set_value(block, "int", "MacRandomizationSetting", &mac.to_string());
+            changed.push("MAC");
+        }
+    }
+
+    if !changed.is_empty() {
+        result.updated.push((settings.ssid.clone(), changed.join(", ")));
+    }
+    Ok(Some(idx))
+}
+
+/// Index and value of the first `<item>` of `<string-array name="WEPKeys">`
+fn find_wep_key(block: &[Event<'_>]) -> Option<(usize, String)> {
+    let start = block.iter().position(|event| {
+        matches!(event, Event::Start(e) if e.name().as_ref() == b"string-array" && has_name(e, "WEPKeys"))
+    })?;
+    block.iter().enumerate().skip(start + 1).find_map(|(idx, event)| match event {
+        Event::Empty(e) if e.name().as_ref() == b"item" => {
+            let value = e.try_get_attribute("value").ok().flatten()?;
+            Some(Some((idx, value.unescape_value().ok()?.into_owned())))
+        }
+        Event::End(_) => Some(None),
+        _ => None,
+    })?
+}
+
+/// Index and `value` attribute of `<boolean name="..." value="..." />` or `<int .../>`
+fn find_value(block: &[Event<'_>], name: &str) -> Option<(usize, String)> {
+    block.iter().enumerate().find_map(|(idx, event)| match event {
+        Event::Empty(e) if has_name(e, name) => {
+            let value = e.try_get_attribute("value").ok().flatten()?;
+            Some((idx, String::from_utf8_lossy(&value.value).into_owned()))
+        }
+        _ => None,
+    })
+}
+
+/// Replace a value element, or add it at the end of `<WifiConfiguration>`
+fn set_value(block: &mut Vec<Event<'_>>, tag: &str, name: &str, value: &str) {
+    let element = Event::Empty(
+        BytesStart::new(tag.to_string()).with_attributes([("name", name), ("value", value)]),
+    );
+    if let Some((idx, _)) = find_value(block, name) {
+        block[idx] = element;
+        return;
+    }
+
+    let section = |event: &Event<'_>| match event {
+        Event::Start(e) => e.name().as_ref() == b"WifiConfiguration",
+        Event::End(e) => e.name().as_ref() == b"WifiConfiguration",
+        _ => false,
+    };
+    let (Some(start), Some(end)) = (block.iter().position(section), block.iter().rposition(section)) else {
+        return;
+    };
+    // Indent like the first child, before the whitespace that precedes the end tag
+    let indent = match (&block[start + 1], &block[end - 1]) {
+        (first @ Event::Text(_), Event::Text(_)) if end > start + 1 => Some(first.clone().into_owned()),
+        _ => None,
+    };
+    match indent {
+        Some(indent) => {
+            block.splice(end - 1..end - 1, [indent, element]);
+        }
+        None => block.insert(end, element),
+    }
+}
+
+fn has_name(e: &BytesStart<'_>, name: &str) -> bool {
+    e.try_get_attribute("name").ok().flatten().is_some_and(|a| a.value.as_ref() == name.as_bytes())
+}
+
+/// Indexes of the start and end tag of `<string name="...">` and its text
+fn find_string(block: &[Event<'_>], name: &str) -> Result<Option<(usize, usize, String)>, String> {
+    let Some(start) = block.iter().position(|event| match event {
+        Event::Start(e) if e.name().as_ref() == b"string" => has_name(e, name),
+        _ => false,
+    }) else {
+        return Ok(None);
+    };
+
+    let mut value = String::new();
+    for (idx, event) in block.iter().enumerate().skip(start + 1) {
+        match event {
+            Event::Text(t) => value.push_str(&t.unescape().map_err(|e| format!("Invalid XML text: {}", e))?),
+            Event::CData(t) => value.push_str(&String::from_utf8_lossy(t)),
+            Event::End(_) => return Ok(Some((start, idx, value))),
+            _ => {}
+        }
+    }
+    Err(format!("Unclosed <string name=\"{}\">", name))
+}
+
+/// Write the networks that were not in the store, before `</NetworkList>`
+fn add_blocks(writer: &mut Writer<Vec<u8>>, pending: &mut Vec<Pending>, result: &mut MergeResult) {
+    for Pending { network, key_type, conflict } in pending.drain(..) {
+        let ssid = network.settings.ssid.clone();
+        match conflict {
+            Some(reason) => result.skipped.push((ssid, reason)),
+            None => {
+                writer.get_mut().extend_from_slice(network_block(network, key_type).as_bytes());
+                result.added.push(ssid);
+            }
+        }
+    }
+}
+
+/// A `<Network>` block laid out like the ones Android writes, with the
+/// sections it requires when loading the store
+fn network_block(local: &LocalNetwork, key_type: &str) -> String {
+    let network = &local.settings;
+    let password = network.password.as_str();
+    let string = |name: &str, value: &str| format!("<string name=\"{}\">{}</string>", name, escape(value));
+    // AllowedKeyManagement is a BitSet: NONE = bit 0, WPA_PSK = 1, SAE = 8, OWE = 9
+    let (num, bits) = match key_type {
+        "WPA_PSK" => (1, "02"),
+        "SAE" => (2, "0001"),
+        "OWE" => (2, "0002"),
+        _ => (1, "01"),
+    };
+
+    let mut lines = vec![
+        "<Network>".to_string(),
+        "<WifiConfiguration>".to_string(),
+        string("ConfigKey", &config_key(&network.ssid, key_type)),
+        string("SSID", &format!("\"{}\"", network.ssid)),
+    ];
+    match key_type {
+        "WPA_PSK" | "SAE" => lines.push(string("PreSharedKey", &psk_value(password))),
+        "WEP" => {
+            lines.push("<string-array name=\"WEPKeys\" num=\"4\">".to_string());
+            lines.push(format!("<item value=\"{}\" />", escape(&wep_value(password))));
+            lines.extend(std::iter::repeat_n("<item value=\"\" />".to_string(), 3));
+            lines.push("</string-array>".to_string());
+            lines.push("<int name=\"WEPTxKeyIndex\" value=\"0\" />".to_string());
+        }
+        _ => {}
+    }
+    lines.extend([
+        format!("<boolean name=\"HiddenSSID\" value=\"{}\" />", network.hidden),
+        format!("<int name=\"Priority\" value=\"{}\" />", local.priority.unwrap_or(0)),
+        format!("<int name=\"MeteredOverride\" value=\"{}\" />", metered_to_android(network.metered)),
+    ]);
+    if let Some(mac) = mac_to_android(&network.mac_address) {
+        lines.push(format!("<int name=\"MacRandomizationSetting\" value=\"{}\" />", mac));
+    }
+    lines.extend([
+        format!("<byte-array name=\"AllowedKeyManagement\" num=\"{}\">{}</byte-array>", num, bits),
+        "</WifiConfiguration>".to_string(),
+        "<NetworkStatus>".to_string(),
+        string("SelectionStatus", "NETWORK_SELECTION_ENABLED"),
+        string("DisableReason", "NETWORK_SELECTION_ENABLE"),
+        "</NetworkStatus>".to_string(),
+        "<IpConfiguration>".to_string(),
+        string("IpAssignment", "DHCP"),
+        string("ProxySettings", "NONE"),
+        "</IpConfiguration>".to_string(),
+        "</Network>".to_string(),
+    ]);
+
+    let mut block = lines.join("\n");
+    block.push('\n');
+    block
+}
+
+fn write_event(writer: &mut Writer<Vec<u8>>, event: Event<'_>) -> Result<(), String> {
+    writer
+        .write_event(event)
+        .map_err(|e| format!("Failed to write XML: {}", e))
+}
+
+fn xml_error(reader: &Reader<&[u8]>, e: quick_xml::Error) -> String {
+    format!("XML error at position {}: {}", reader.buffer_position(), e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::network::{EnterpriseSettings, MacAddress};
+
+    const STORE: &str = r#"<?xml version='1.0' encoding='utf-8' standalone='yes' ?>
+<WifiConfigStoreData>
+<int name="Version" value="3" />
+<NetworkList>
+<Network>
+<WifiConfiguration>
+<string name="ConfigKey">&quot;Home&quot;WPA_PSK</string>
+<string name="SSID">&quot;Home&quot;</string>
+<string name="PreSharedKey">&quot;old-password&quot;</string>
+<int name="Priority" value="7" />
+<int name="MeteredOverride" value="1" />
+<byte-array name="AllowedKeyManagement" num="1">02</byte-array>
+<FutureElement name="Unknown">kept</FutureElement>
+</WifiConfiguration>
+<NetworkStatus>
+<string name="SelectionStatus">NETWORK_SELECTION_ENABLED</string>
+</NetworkStatus>
+</Network>
+<Network>
+<WifiConfiguration>
+<string name="ConfigKey">&quot;Phone only&quot;NONE</string>
+<string name="SSID">&quot;Phone only&quot;</string>
+<byte-array name="AllowedKeyManagement" num="1">01</byte-array>
+</WifiConfiguration>
+</Network>
+<Network>
+<WifiConfiguration>
+<string name="ConfigKey">&quot;Old&quot;WEP</string>
+<string name="SSID">&quot;Old&quot;</string>
+<string-array name="WEPKeys" num="4">
+<item value="&quot;abcde&quot;" />
+<item value="" />
+<item value="" />
+<item value="" />
+</string-array>
+<byte-array name="AllowedKeyManagement" num="1">01</byte-array>
+</WifiConfiguration>
+</Network>
+</NetworkList>
+<DeletedEphemeralSsidList />
+</WifiConfigStoreData>
+"#;
+
+    fn local(ssid: &str, security: Security, password: &str) -> LocalNetwork {
+        LocalNetwork {
+            settings: NetworkSettings {
+                ssid: ssid.to_string(),
+                password: password.to_string(),
+                security,
+                hidden: false,
+                autoconnect: true,
+                priority: 0,
+                metered: Metered::Unknown,
+                mac_address: MacAddress::Default,
+                enterprise: EnterpriseSettings::default(),
+            },
+            priority: None,
+        }
+    }
+
+    #[test]
+    fn unchanged_store_is_byte_identical() {
+        let result = merge(STORE, &[local("Home", Security::WpaPsk, "old-password")]).unwrap();
+        assert_eq!(result.xml, STORE);
+        assert!(!result.has_changes());
+
+        let example = include_str!("../../../data/examples/WifiConfigStore.xml");
+        assert_eq!(merge(example, &[]).unwrap().xml, example);
+    }
+
+    #[test]
+    fn changed_psk_is_replaced_and_the_rest_kept() {
+        let result = merge(STORE, &[local("Home", Security::WpaPsk, "new & <password>")]).unwrap();
+        assert_eq!(result.updated, vec![("Home".to_string(), "password".to_string())]);
+        assert_eq!(
+            result.xml,
+            STORE.replace("old-password", "new &amp; &lt;password&gt;")
+        );
+        assert!(result.xml.contains("<FutureElement name=\"Unknown\">kept</FutureElement>"));
+        assert!(result.xml.contains("&quot;Phone only&quot;NONE"));
+
+        let networks = android_xml::parse_str(&result.xml).unwrap();
+        assert_eq!(networks[0].psk.as_deref(), Some("new & <password>"));
+    }
+
+    #[test]
+    fn device_values_are_kept_unless_the_keyfile_sets_them() {
+        // No autoconnect-priority and no metered setting: priority 7 and metered stay
+        let result = merge(STORE, &[local("Home", Security::WpaPsk, "old-password")]).unwrap();
+        assert!(result.xml.contains("<int name=\"Priority\" value=\"7\" />"));
+        assert!(result.xml.contains("<int name=\"MeteredOverride\" value=\"1\" />"));
+
+        let mut home = local("Home", Security::WpaPsk, "old-password");
+        home.priority = Some(3);
+        home.settings.metered = Metered::No;
+        home.settings.hidden = true;
+        let result = merge(STORE, &[home]).unwrap();
+        assert_eq!(result.updated, vec![("Home".to_string(), "hidden, priority, metered".to_string())]);
+        let network = &android_xml::parse_str(&result.xml).unwrap()[0];
+        assert_eq!((network.priority, network.metered, network.hidden), (3, Metered::No, true));
+    }
+
+    #[test]
+    fn unhiding_a_network_reaches_the_phone() {
+        let hidden = STORE.replace(
+            "<int name=\"Priority\" value=\"7\" />",
+            "<boolean name=\"HiddenSSID\" value=\"true\" />\n<int name=\"Priority\" value=\"7\" />",
+        );
+        let result = merge(&hidden, &[local("Home", Security::WpaPsk, "old-password")]).unwrap();
+        assert_eq!(result.updated, vec![("Home".to_string(), "hidden".to_string())]);
+        assert!(result.xml.contains("name=\"HiddenSSID\" value=\"false\""));
+        assert!(!android_xml::parse_str(&result.xml).unwrap()[0].hidden);
+
+        // A block without the key stays as it is
+        let result = merge(STORE, &[local("Home", Security::WpaPsk, "old-password")]).unwrap();
+        assert!(!result.xml.contains("HiddenSSID"));
+    }
+
+    #[test]
+    fn added_block_parses_back() {
+        let mut cafe = local("Café \"5\"", Security::Sae, "secret123");
+        cafe.priority = Some(4);
+        let result = merge(STORE, &[cafe]).unwrap();
+        assert_eq!(result.added, vec!["Café \"5\"".to_string()]);
+        assert!(result.xml.contains("<byte-array name=\"AllowedKeyManagement\" num=\"2\">0001</byte-array>"));
+        assert!(result.xml.contains("<DeletedEphemeralSsidList />"));
+
+        let networks = android_xml::parse_str(&result.xml).unwrap();
+        let added = networks.iter().find(|n| n.ssid == "Café \"5\"").unwrap();
+        assert_eq!(added.config_key, "\"Café \"5\"\"SAE");
+        assert_eq!(added.security, Security::Sae);
+        assert_eq!(added.psk.as_deref(), Some("secret123"));
+        assert_eq!(added.priority, 4);
+        assert_eq!(networks.len(), 4);
+    }
+
+    #[test]
+    fn other_key_type_is_reported_not_duplicated() {
+        let result = merge(STORE, &[local("Phone only", Security::WpaPsk, "password1")]).unwrap();
+        assert!(result.added.is_empty());
+        assert_eq!(result.skipped.len(), 1);
+        assert!(result.skipped[0].1.contains("NONE"));
+        assert_eq!(result.xml, STORE);
+
+        // Transition mode matches a device entry stored as WPA_PSK
+        let result = merge(STORE, &[local("Home", Security::Transition, "old-password")]).unwrap();
+        assert!(result.skipped.is_empty() && result.added.is_empty());
+    }
+
+    #[test]
+    fn wep_key_is_updated() {
+        let result = merge(STORE, &[local("Old", Security::Wep, "0123456789")]).unwrap();
+        assert_eq!(result.updated, vec![("Old".to_string(), "WEP key".to_string())]);
+        let network = android_xml::parse_str(&result.xml)
+            .unwrap()
+            .into_iter()
+            .find(|n| n.ssid == "Old")
+            .unwrap();
+        assert_eq!(network.wep_keys, vec!["0123456789".to_string()]);
+    }
+
+    #[test]
+    fn store_without_network_list_is_rejected() {
+        assert!(merge("<WifiConfigStoreData></WifiConfigStoreData>", &[]).is_err());
+    }
+}
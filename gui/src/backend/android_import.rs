@@ -0,0 +1,215 @@
+use std::fs;
+use std::path::Path;
+use crate::backend::android_listing;
+use crate::backend::android_xml::{self, eap_method, phase2_method, AndroidNetwork, Credentials};
+use crate::backend::config::Config;
+use crate::backend::nmconnection;
+use crate::models::network::{EnterpriseSettings, MacAddress, NetworkSettings, Security};
+
+/// What an Android import did, by SSID
+#[derive(Debug, Clone, Default)]
+pub struct ImportResult {
+    pub created: Vec<String>,
+    /// Networks that already have a connection file, left as they are
+    pub existing: Vec<String>,
+    /// Networks that cannot be imported, and why
+    pub skipped: Vec<(String, String)>,
+    /// Settings of created networks that could not be carried over
+    pub notes: Vec<(String, String)>,
+}
+
+/// Networks to preview: the pulled WifiConfigStore.xml, or the non-root
+/// listing if pull-android fell back to it after the XML was pulled
+pub fn load(config: &Config) -> Result<Vec<AndroidNetwork>, String> {
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified(&config.xml_file), modified(&config.listing_file)) {
+        (None, Some(_)) => android_listing::parse_file(&config.listing_file),
+        (Some(xml), Some(listing)) if listing > xml => android_listing::parse_file(&config.listing_file),
+        _ => android_xml::parse_file(&config.xml_file),
+    }
+}
+
+/// Why `network` cannot become a connection file, if it cannot
+pub fn import_issue(network: &AndroidNetwork) -> Option<String> {
+    // Never fall back to an open profile for a network that has a key
+    match network.credentials {
+        Credentials::Encrypted => {
+            return Some("credentials unavailable, encrypted with the device keystore".to_string())
+        }
+        Credentials::NotPulled if !matches!(network.security, Security::Open | Security::Owe) => {
+            return Some("credentials unavailable, the listing has no passwords; pull as root".to_string())
+        }
+        _ => {}
+    }
+    match &network.security {
+        Security::Enterprise => enterprise_settings(network).err(),
+        Security::Unknown(mode) => Some(format!("{} is not supported by NetworkManager", mode)),
+        security if security.requires_password() && password(network).is_empty() => {
+            Some("no password stored".to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Settings the connection file will be missing, to be filled in by hand
+pub fn import_notes(network: &AndroidNetwork) -> Vec<String> {
+    match network.security {
+        Security::Enterprise => enterprise_settings(network)
+            .map(|(_, notes)| notes)
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Create connection files for the networks that have none yet, like
+/// `cmd_import_android`, with the security decoded from the store
+pub fn import(config: &Config, networks: &[AndroidNetwork]) -> Result<ImportResult, String> {
+    let mut result = ImportResult::default();
+
+    for network in networks {
+        let filename = nmconnection::connection_filename(&network.ssid);
+        if config.local_dir.join(&filename).exists() {
+            result.existing.push(network.ssid.clone());
+            continue;
+        }
+        if let Some(issue) = import_issue(network) {
+            result.skipped.push((network.ssid.clone(), issue));
+            continue;
+        }
+
+        let (enterprise, notes) = match network.security {
+            Security::Enterprise => enterprise_settings(network)?,
+            _ => (EnterpriseSettings::default(), Vec::new()),
+        };
+        nmconnection::create_network(
+            config,
+            &network.ssid,
+            password(network),
+            &network.security,
+            &enterprise,
+        )?;
+        apply_settings(config, &filename, network)?;
+        result.created.push(network.ssid.clone());
+        result.notes.extend(notes.into_iter().map(|note| (network.ssid.clone(), note)));
+    }
+
+    Ok(result)
+}
+
+/// Carry over the settings the profile template leaves at their defaults
+fn apply_settings(config: &Config, filename: &str, network: &AndroidNetwork) -> Result<(), String> {
+    let path = config.local_dir.join(filename);
+    let original = nmconnection::load_settings(&path)?;
+    let updated = NetworkSettings {
+        hidden: network.hidden,
+        // NetworkManager only accepts -999..999
+        priority: network.priority.clamp(-999, 999) as i32,
+        metered: network.metered,
+        mac_address: match (&network.mac_address, &network.randomized_mac) {
+            // Keep the address the network already knows the phone by
+            (MacAddress::Stable, Some(mac)) => MacAddress::Other(mac.clone()),
+            (mac_address, _) => mac_address.clone(),
+        },
+        ..original.clone()
+    };
+    nmconnection::update_network(config, &path, &original, &updated)?;
+    Ok(())
+}
+
+/// `[802-1x]` settings of an EAP network, and what did not carry over.
+/// Certificates and keys live in the Android keystore, which is not part of
+/// WifiConfigStore.xml, so only their aliases are known.
+fn enterprise_settings(network: &AndroidNetwork) -> Result<(EnterpriseSettings, Vec<String>), String> {
+    let Some(android) = &network.enterprise else {
+        return Err("no enterprise settings stored".to_string());
+    };
+    let mut notes = Vec::new();
+
+    let eap = match android.eap_method {
+        eap_method::PEAP => "peap",
+        eap_method::TLS => "tls",
+        eap_method::TTLS => "ttls",
+        eap_method::PWD => return Err("EAP-PWD is not supported".to_string()),
+        eap_method::SIM | eap_method::AKA | eap_method::AKA_PRIME => {
+            return Err("EAP-SIM/AKA authenticate with the phone's SIM card".to_string())
+        }
+        eap_method::UNAUTH_TLS => return Err("unauthenticated EAP-TLS is not supported".to_string()),
+        eap_method::WAPI_CERT => return Err("wapi-cert is not supported by NetworkManager".to_string()),
+        other => return Err(format!("unknown EAP method {}", other)),
+    };
+
+    let phase2_auth = match (eap, android.phase2_method) {
+        ("tls", _) => "",
+        (_, phase2_method::PAP) => "pap",
+        (_, phase2_method::MSCHAP) => "mschap",
+        (_, phase2_method::MSCHAPV2) => "mschapv2",
+        (_, phase2_method::GTC) => "gtc",
+        (_, phase2_method::NONE) => {
+            notes.push("no phase 2 method stored, MSCHAPv2 assumed".to_string());
+            "mschapv2"
+        }
+        (_, phase2_method::SIM | phase2_method::AKA | phase2_method::AKA_PRIME) => {
+            return Err("SIM-based phase 2 methods are not supported".to_string())
+        }
+        (_, other) => return Err(format!("unknown phase 2 method {}", other)),
+    };
+
+    if !android.ca_cert.is_empty() {
+        notes.push(format!(
+            "CA certificate {} is held in the Android keystore; add it in the edit dialog",
+            keystore_aliases(&android.ca_cert)
+        ));
+    } else if !android.ca_path.is_empty() {
+        notes.push("Android checked the server against its system CAs; add a CA certificate in the edit dialog".to_string());
+    }
+    if eap == "tls" {
+        let alias = [&android.client_cert, &android.private_key]
+            .into_iter()
+            .find(|a| !a.is_empty())
+            .map(|a| format!(" {}", keystore_aliases(a)))
+            .unwrap_or_default();
+        notes.push(format!(
+            "client certificate and private key{} are held in the Android keystore; add them in the edit dialog",
+            alias
+        ));
+    }
+    if !android.alt_subject_match.is_empty() {
+        notes.push(format!("server name match {} is not carried over", android.alt_subject_match));
+    }
+
+    let settings = EnterpriseSettings {
+        eap: eap.to_string(),
+        identity: android.identity.clone(),
+        anonymous_identity: android.anonymous_identity.clone(),
+        phase2_auth: phase2_auth.to_string(),
+        domain_suffix_match: android.domain_suffix_match.clone(),
+        ..EnterpriseSettings::default()
+    };
+    Ok((settings, notes))
+}
+
+/// `keystore://CACERT_name other` as `'name', 'other'`
+fn keystore_aliases(references: &str) -> String {
+    references
+        .split_whitespace()
+        .map(|reference| {
+            let alias = reference.strip_prefix("keystore://").unwrap_or(reference);
+            let alias = ["CACERT_", "USRCERT_", "USRPKEY_"]
+                .iter()
+                .find_map(|prefix| alias.strip_prefix(prefix))
+                .unwrap_or(alias);
+            format!("'{}'", alias)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The key the profile needs: the first WEP key for WEP, the EAP password for
+/// enterprise networks, else the pre-shared key
+fn password(network: &AndroidNetwork) -> &str {
+    match network.security {
+        Security::Wep => network.wep_keys.first().map(String::as_str).unwrap_or_default(),
+        Security::Enterprise => network.enterprise.as_ref().map(|e| e.password.as_str()).unwrap_or_default(),
+        _ => network.psk.as_deref().unwrap_or_default(),
+    }
+}
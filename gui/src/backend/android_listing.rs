@@ -0,0 +1,93 @@
+use std::fs;
+use std::path::Path;
+use crate::backend::android_xml::{AndroidNetwork, Credentials};
+use crate::models::network::Security;
+
+/// Parse a listing saved by pull-android when the device has no root
+pub fn parse_file(path: &Path) -> Result<Vec<AndroidNetwork>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(parse_str(&content))
+}
+
+/// Parse `cmd wifi list-networks` output: a header, then one line per network
+/// with its id, SSID and security type. SSIDs may contain spaces, so the id is
+/// the first column and the security type the last one.
+pub fn parse_str(content: &str) -> Vec<AndroidNetwork> {
+    content
+        .lines()
+        .filter(|line| !line.starts_with("Network Id"))
+        .filter_map(|line| {
+            let line = line.trim();
+            let (id, rest) = line.split_once(char::is_whitespace)?;
+            id.parse::<u32>().ok()?;
+            let (ssid, security) = rest.trim().rsplit_once(char::is_whitespace)?;
+            let ssid = ssid.trim();
+            if ssid.is_empty() {
+                return None;
+            }
+            Some(AndroidNetwork {
+                ssid: ssid.to_string(),
+                security: security_from_listing(security),
+                credentials: Credentials::NotPulled,
+                ..AndroidNetwork::default()
+            })
+        })
+        .collect()
+}
+
+/// Security type names of `WifiShellCommand`
+fn security_from_listing(security: &str) -> Security {
+    match security {
+        "open" => Security::Open,
+        "owe" => Security::Owe,
+        "wep" => Security::Wep,
+        "wpa2" => Security::WpaPsk,
+        "wpa3" => Security::Sae,
+        "eap" => Security::Enterprise,
+        other => Security::Unknown(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = "\
+Network Id      SSID                         Security type
+0               Home                         wpa2
+1               Coffee Shop Guest            open
+2               Old router                   wep
+3               New router                   wpa3
+4               Airport                      owe
+5               Office                       eap
+6               Somewhere                    wapi
+";
+
+    #[test]
+    fn lines_are_split_around_ssids_with_spaces() {
+        let networks = parse_str(LISTING);
+        let parsed: Vec<(&str, &Security)> =
+            networks.iter().map(|n| (n.ssid.as_str(), &n.security)).collect();
+        assert_eq!(
+            parsed,
+            vec![
+                ("Home", &Security::WpaPsk),
+                ("Coffee Shop Guest", &Security::Open),
+                ("Old router", &Security::Wep),
+                ("New router", &Security::Sae),
+                ("Airport", &Security::Owe),
+                ("Office", &Security::Enterprise),
+                ("Somewhere", &Security::Unknown("wapi".to_string())),
+            ]
+        );
+        assert!(networks.iter().all(|n| matches!(n.credentials, Credentials::NotPulled)));
+    }
+
+    #[test]
+    fn header_and_empty_output_give_no_networks() {
+        assert!(parse_str("").is_empty());
+        assert!(parse_str("\n").is_empty());
+        assert!(parse_str("Network Id      SSID                         Security type\n").is_empty());
+    }
+}
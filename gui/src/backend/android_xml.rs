@@ -0,0 +1,763 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use crate::models::network::{MacAddress, Metered, Security};
+
+/// A single typed value from WifiConfigStore.xml (`<string name="...">`, `<int .../>`, ...)
+#[derive(Debug, Clone, PartialEq)]
+pub enum XmlValue {
+    Null,
+    String(String),
+    Int(i64),
+    Bool(bool),
+    Bytes(Vec<u8>),
+    StringArray(Vec<String>),
+    /// A nested list of sections, e.g. `<SecurityParamsList>`
+    Sections(Vec<XmlSection>),
+    /// A value stored as `<EncryptedData>`, which only the device keystore can decrypt
+    Encrypted,
+}
+
+/// Named values of one section, e.g. `<WifiConfiguration>`
+pub type XmlSection = BTreeMap<String, XmlValue>;
+
+/// `WifiConfiguration.KeyMgmt` bits of the `AllowedKeyManagement` BitSet
+mod key_mgmt {
+    pub const NONE: usize = 0;
+    pub const WPA_PSK: usize = 1;
+    pub const WPA_EAP: usize = 2;
+    pub const IEEE8021X: usize = 3;
+    pub const WPA2_PSK: usize = 4;
+    pub const FT_PSK: usize = 6;
+    pub const FT_EAP: usize = 7;
+    pub const SAE: usize = 8;
+    pub const OWE: usize = 9;
+    pub const SUITE_B_192: usize = 10;
+    pub const WPA_PSK_SHA256: usize = 11;
+    pub const WPA_EAP_SHA256: usize = 12;
+    pub const WAPI_PSK: usize = 13;
+    pub const WAPI_CERT: usize = 14;
+}
+
+/// `WifiConfiguration.SECURITY_TYPE_*` values of `SecurityParams` (Android 12+)
+mod security_type {
+    pub const OPEN: i64 = 0;
+    pub const WEP: i64 = 1;
+    pub const PSK: i64 = 2;
+    pub const EAP: i64 = 3;
+    pub const SAE: i64 = 4;
+    pub const EAP_SUITE_B: i64 = 5;
+    pub const OWE: i64 = 6;
+    pub const WAPI_PSK: i64 = 7;
+    pub const WAPI_CERT: i64 = 8;
+    pub const EAP_WPA3_ENTERPRISE: i64 = 9;
+    pub const PASSPOINT_R1_R2: i64 = 11;
+    pub const PASSPOINT_R3: i64 = 12;
+}
+
+/// Whether the keys of a network could be read
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Credentials {
+    #[default]
+    Readable,
+    /// Stored as `<EncryptedData>`, bound to the device keystore
+    Encrypted,
+    /// From the non-root device listing, which has no keys
+    NotPulled,
+}
+
+/// A network entry from `WifiConfigStoreData/NetworkList/Network/WifiConfiguration`
+#[derive(Debug, Clone, Default)]
+pub struct AndroidNetwork {
+    pub config_key: String,
+    pub ssid: String,
+    /// Decoded from `SecurityParamsList`, else `AllowedKeyManagement`, else
+    /// from which keys or EAP settings are stored
+    pub security: Security,
+    pub psk: Option<String>,
+    pub wep_keys: Vec<String>,
+    pub priority: i64,
+    pub hidden: bool,
+    pub metered: Metered,
+    pub mac_address: MacAddress,
+    pub randomized_mac: Option<String>,
+    /// The `<WifiEnterpriseConfiguration>` next to the network, if it uses EAP
+    pub enterprise: Option<AndroidEnterprise>,
+    pub credentials: Credentials,
+}
+
+/// `WifiEnterpriseConfig.Eap` values of `EapMethod`
+pub mod eap_method {
+    pub const NONE: i64 = -1;
+    pub const PEAP: i64 = 0;
+    pub const TLS: i64 = 1;
+    pub const TTLS: i64 = 2;
+    pub const PWD: i64 = 3;
+    pub const SIM: i64 = 4;
+    pub const AKA: i64 = 5;
+    pub const AKA_PRIME: i64 = 6;
+    pub const UNAUTH_TLS: i64 = 7;
+    pub const WAPI_CERT: i64 = 8;
+}
+
+/// `WifiEnterpriseConfig.Phase2` values of `Phase2Method`
+pub mod phase2_method {
+    pub const NONE: i64 = 0;
+    pub const PAP: i64 = 1;
+    pub const MSCHAP: i64 = 2;
+    pub const MSCHAPV2: i64 = 3;
+    pub const GTC: i64 = 4;
+    pub const SIM: i64 = 5;
+    pub const AKA: i64 = 6;
+    pub const AKA_PRIME: i64 = 7;
+}
+
+/// EAP settings from `WifiEnterpriseConfiguration`, as Android stores them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AndroidEnterprise {
+    pub eap_method: i64,
+    pub phase2_method: i64,
+    pub identity: String,
+    pub anonymous_identity: String,
+    pub password: String,
+    /// `keystore://CACERT_<alias>` references, space separated
+    pub ca_cert: String,
+    /// Set to the system CA directory when Android trusts its built-in CAs
+    pub ca_path: String,
+    /// `keystore://USRCERT_<alias>` reference of the client certificate
+    pub client_cert: String,
+    /// Keystore alias of the private key (`PrivateKeyId` or `KeyChainAlias`)
+    pub private_key: String,
+    pub domain_suffix_match: String,
+    pub alt_subject_match: String,
+}
+
+impl AndroidEnterprise {
+    fn from_section(section: &XmlSection) -> Option<Self> {
+        let eap_method = get_int(section, "EapMethod").unwrap_or(eap_method::NONE);
+        if eap_method == eap_method::NONE {
+            return None;
+        }
+        let string = |name: &str| get_string(section, name).unwrap_or_default();
+
+        Some(Self {
+            eap_method,
+            phase2_method: get_int(section, "Phase2Method").unwrap_or(phase2_method::NONE),
+            identity: string("Identity"),
+            anonymous_identity: string("AnonIdentity"),
+            password: string("Password"),
+            ca_cert: string("CaCert"),
+            ca_path: string("CaPath"),
+            client_cert: string("ClientCert"),
+            private_key: Some(string("PrivateKeyId"))
+                .filter(|k| !k.is_empty())
+                .unwrap_or_else(|| string("KeyChainAlias")),
+            domain_suffix_match: string("DomSuffixMatch"),
+            alt_subject_match: string("AltSubjectMatch"),
+        })
+    }
+}
+
+impl AndroidNetwork {
+    fn from_section(section: &XmlSection, enterprise: Option<&XmlSection>) -> Option<Self> {
+        let ssid = match section.get("SSID") {
+            Some(XmlValue::String(s)) => decode_ssid(s),
+            _ => return None,
+        };
+        if ssid.is_empty() {
+            return None;
+        }
+
+        let wep_keys = match section.get("WEPKeys") {
+            Some(XmlValue::StringArray(keys)) => keys
+                .iter()
+                .map(|k| unquote(k).to_string())
+                .filter(|k| !k.is_empty())
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let psk = get_string(section, "PreSharedKey").map(|p| unquote(&p).to_string());
+        let encrypted = |section: &XmlSection| section.values().any(|v| *v == XmlValue::Encrypted);
+        let credentials = if encrypted(section) || enterprise.is_some_and(encrypted) {
+            Credentials::Encrypted
+        } else {
+            Credentials::Readable
+        };
+        let enterprise = enterprise.and_then(AndroidEnterprise::from_section);
+        let security = security_from_params(section)
+            .or_else(|| security_from_key_mgmt(section, !wep_keys.is_empty()))
+            .unwrap_or_else(|| {
+                if enterprise.is_some() {
+                    Security::Enterprise
+                } else if psk.is_some() || credentials == Credentials::Encrypted {
+                    Security::WpaPsk
+                } else if !wep_keys.is_empty() {
+                    Security::Wep
+                } else {
+                    Security::Open
+                }
+            });
+
+        Some(Self {
+            config_key: get_string(section, "ConfigKey").unwrap_or_default(),
+            ssid,
+            security,
+            psk,
+            wep_keys,
+            priority: get_int(section, "Priority").unwrap_or(0),
+            hidden: get_bool(section, "HiddenSSID").unwrap_or(false),
+            metered: get_int(section, "MeteredOverride").map(metered_from_android).unwrap_or_default(),
+            mac_address: get_int(section, "MacRandomizationSetting")
+                .map(mac_from_android)
+                .unwrap_or_default(),
+            randomized_mac: get_string(section, "RandomizedMacAddress")
+                .filter(|m| m != "02:00:00:00:00:00"),
+            enterprise,
+            credentials,
+        })
+    }
+}
+
+/// `MeteredOverride`: 0 = none, 1 = metered, 2 = not metered
+pub fn metered_from_android(value: i64) -> Metered {
+    match value {
+        1 => Metered::Yes,
+        2 => Metered::No,
+        _ => Metered::Unknown,
+    }
+}
+
+pub fn metered_to_android(metered: Metered) -> i64 {
+    match metered {
+        Metered::Unknown => 0,
+        Metered::Yes => 1,
+        Metered::No => 2,
+    }
+}
+
+/// `MacRandomizationSetting`: 0 = device MAC, 1 = persistent random,
+/// 2 = new random per connection, 3 = Android decides (like NetworkManager's default)
+pub fn mac_from_android(value: i64) -> MacAddress {
+    match value {
+        0 => MacAddress::Permanent,
+        1 => MacAddress::Stable,
+        2 => MacAddress::Random,
+        _ => MacAddress::Default,
+    }
+}
+
+/// `None` for settings Android has no value for, which are left to the phone
+pub fn mac_to_android(mac_address: &MacAddress) -> Option<i64> {
+    match mac_address {
+        MacAddress::Permanent => Some(0),
+        MacAddress::Stable => Some(1),
+        MacAddress::Random => Some(2),
+        MacAddress::Default | MacAddress::Other(_) => None,
+    }
+}
+
+/// Security of an Android 12+ network from its enabled `SecurityParams`.
+/// Types Android added itself (e.g. SAE next to PSK, OWE next to open) only
+/// count when nothing else is enabled.
+fn security_from_params(section: &XmlSection) -> Option<Security> {
+    let Some(XmlValue::Sections(params)) = section.get("SecurityParamsList") else {
+        return None;
+    };
+    let enabled: Vec<&XmlSection> = params
+        .iter()
+        .filter(|p| get_bool(p, "IsEnabled").unwrap_or(true))
+        .collect();
+    let original: Vec<&XmlSection> = enabled
+        .iter()
+        .copied()
+        .filter(|p| !get_bool(p, "IsAddedByAutoUpgrade").unwrap_or(false))
+        .collect();
+    let types: Vec<i64> = if original.is_empty() { &enabled } else { &original }
+        .iter()
+        .filter_map(|p| get_int(p, "SecurityType"))
+        .collect();
+    let has = |t: i64| types.contains(&t);
+
+    use security_type::*;
+    Some(if has(EAP) || has(EAP_WPA3_ENTERPRISE) || has(EAP_SUITE_B) || has(PASSPOINT_R1_R2) || has(PASSPOINT_R3) {
+        Security::Enterprise
+    } else if has(SAE) && has(PSK) {
+        Security::Transition
+    } else if has(SAE) {
+        Security::Sae
+    } else if has(PSK) {
+        Security::WpaPsk
+    } else if has(WAPI_PSK) {
+        Security::Unknown("wapi-psk".to_string())
+    } else if has(WAPI_CERT) {
+        Security::Unknown("wapi-cert".to_string())
+    } else if has(OWE) {
+        Security::Owe
+    } else if has(WEP) {
+        Security::Wep
+    } else if has(OPEN) {
+        Security::Open
+    } else {
+        return None;
+    })
+}
+
+/// Security of an older network from its `AllowedKeyManagement` BitSet
+fn security_from_key_mgmt(section: &XmlSection, has_wep_keys: bool) -> Option<Security> {
+    let Some(XmlValue::Bytes(bytes)) = section.get("AllowedKeyManagement") else {
+        return None;
+    };
+    // BitSet.toByteArray() is little-endian: bit n is in byte n / 8
+    let has = |bit: usize| bytes.get(bit / 8).is_some_and(|b| b & (1 << (bit % 8)) != 0);
+
+    use key_mgmt::*;
+    let psk = has(WPA_PSK) || has(WPA2_PSK) || has(FT_PSK) || has(WPA_PSK_SHA256);
+    Some(if has(WPA_EAP) || has(IEEE8021X) || has(FT_EAP) || has(SUITE_B_192) || has(WPA_EAP_SHA256) {
+        Security::Enterprise
+    } else if has(SAE) && psk {
+        Security::Transition
+    } else if has(SAE) {
+        Security::Sae
+    } else if psk {
+        Security::WpaPsk
+    } else if has(WAPI_PSK) {
+        Security::Unknown("wapi-psk".to_string())
+    } else if has(WAPI_CERT) {
+        Security::Unknown("wapi-cert".to_string())
+    } else if has(OWE) {
+        Security::Owe
+    } else if has(NONE) && has_wep_keys {
+        Security::Wep
+    } else if has(NONE) {
+        Security::Open
+    } else {
+        return None;
+    })
+}
+
+/// Parse a WifiConfigStore.xml file from disk
+pub fn parse_file(path: &Path) -> Result<Vec<AndroidNetwork>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse_str(&content)
+}
+
+/// Parse WifiConfigStore.xml content into typed network records
+pub fn parse_str(content: &str) -> Result<Vec<AndroidNetwork>, String> {
+    let mut reader = Reader::from_str(content);
+    let mut networks = Vec::new();
+    let mut in_network_list = false;
+
+    loop {
+        match reader.read_event().map_err(|e| xml_error(&reader, e))? {
+            Event::Start(e) => match e.name().as_ref() {
+                b"NetworkList" => in_network_list = true,
+                b"Network" if in_network_list => {
+                    if let Some(network) = read_network(&mut reader)? {
+                        networks.push(network);
+                    }
+                }
+                _ => {}
+            },
+            Event::End(e) if e.name().as_ref() == b"NetworkList" => in_network_list = false,
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(networks)
+}
+
+/// Read the sections of one `<Network>` block; the enterprise settings are a
+/// sibling of `<WifiConfiguration>`, not part of it
+fn read_network(reader: &mut Reader<&[u8]>) -> Result<Option<AndroidNetwork>, String> {
+    let mut configuration = None;
+    let mut enterprise = None;
+
+    loop {
+        match reader.read_event().map_err(|e| xml_error(reader, e))? {
+            Event::Start(e) => match e.name().as_ref() {
+                b"WifiConfiguration" => configuration = Some(read_section(reader, b"WifiConfiguration")?),
+                b"WifiEnterpriseConfiguration" => {
+                    enterprise = Some(read_section(reader, b"WifiEnterpriseConfiguration")?)
+                }
+                _ => {
+                    reader
+                        .read_to_end(e.name())
+                        .map_err(|err| xml_error(reader, err))?;
+                }
+            },
+            Event::End(e) if e.name().as_ref() == b"Network" => break,
+            Event::Eof => return Err("Unexpected end of XML inside <Network>".to_string()),
+            _ => {}
+        }
+    }
+
+    Ok(configuration.and_then(|section| AndroidNetwork::from_section(&section, enterprise.as_ref())))
+}
+
+/// Read named values until the closing tag of the current section.
+/// Nested `...List` sections are read as `XmlValue::Sections` under their tag
+/// name, encrypted values (`<PreSharedKey><EncryptedData>...`) as
+/// `XmlValue::Encrypted`; other nested sections are skipped.
+fn read_section(reader: &mut Reader<&[u8]>, end: &[u8]) -> Result<XmlSection, String> {
+    let mut section = XmlSection::new();
+
+    loop {
+        match reader.read_event().map_err(|e| xml_error(reader, e))? {
+            Event::Empty(e) => {
+                if let Some(name) = attribute(&e, b"name")? {
+                    let value = match e.name().as_ref() {
+                        b"null" => XmlValue::Null,
+                        b"string" => XmlValue::String(String::new()),
+                        b"int" | b"long" => XmlValue::Int(
+                            attribute(&e, b"value")?
+                                .and_then(|v| v.parse().ok())
+                                .unwrap_or(0),
+                        ),
+                        b"boolean" => XmlValue::Bool(
+                            attribute(&e, b"value")?.is_some_and(|v| v == "true"),
+                        ),
+                        b"byte-array" => XmlValue::Bytes(Vec::new()),
+                        b"string-array" => XmlValue::StringArray(Vec::new()),
+                        _ => continue,
+                    };
+                    section.insert(name, value);
+                }
+            }
+            Event::Start(e) => {
+                let tag = e.name().as_ref().to_vec();
+                let name = attribute(&e, b"name")?;
+                match (tag.as_slice(), name) {
+                    (b"string", Some(name)) => {
+                        let text = read_text(reader, &tag)?;
+                        section.insert(name, XmlValue::String(text));
+                    }
+                    (b"byte-array", Some(name)) => {
+                        let text = read_text(reader, &tag)?;
+                        section.insert(name, XmlValue::Bytes(decode_hex(text.trim()).unwrap_or_default()));
+                    }
+                    (b"string-array", Some(name)) => {
+                        let items = read_items(reader, &tag)?;
+                        section.insert(name, XmlValue::StringArray(items));
+                    }
+                    (list, None) if list.ends_with(b"List") => {
+                        let sections = read_sections(reader, &tag)?;
+                        section.insert(String::from_utf8_lossy(&tag).into_owned(), XmlValue::Sections(sections));
+                    }
+                    (_, None) => {
+                        let nested = read_section(reader, &tag)?;
+                        if tag == b"EncryptedData" || nested.contains_key("EncryptedData") {
+                            section.insert(String::from_utf8_lossy(&tag).into_owned(), XmlValue::Encrypted);
+                        }
+                    }
+                    _ => {
+                        reader
+                            .read_to_end(e.name())
+                            .map_err(|err| xml_error(reader, err))?;
+                    }
+                }
+            }
+            Event::End(e) if e.name().as_ref() == end => break,
+            Event::Eof => return Err("Unexpected end of XML inside section".to_string()),
+            _ => {}
+        }
+    }
+
+    Ok(section)
+}
+
+/// Read each child section of a list until its closing tag
+fn read_sections(reader: &mut Reader<&[u8]>, end: &[u8]) -> Result<Vec<XmlSection>, String> {
+    let mut sections = Vec::new();
+
+    loop {
+        match reader.read_event().map_err(|e| xml_error(reader, e))? {
+            Event::Start(e) => {
+                let tag = e.name().as_ref().to_vec();
+                sections.push(read_section(reader, &tag)?);
+            }
+            Event::End(e) if e.name().as_ref() == end => break,
+            Event::Eof => return Err("Unexpected end of XML inside list".to_string()),
+            _ => {}
+        }
+    }
+
+    Ok(sections)
+}
+
+/// Collect unescaped text (including CDATA) until the closing tag
+fn read_text(reader: &mut Reader<&[u8]>, end: &[u8]) -> Result<String, String> {
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event().map_err(|e| xml_error(reader, e))? {
+            Event::Text(t) => text.push_str(&t.unescape().map_err(|e| xml_error(reader, e))?),
+            Event::CData(t) => text.push_str(&String::from_utf8_lossy(&t)),
+            Event::End(e) if e.name().as_ref() == end => break,
+            Event::Eof => return Err("Unexpected end of XML inside value".to_string()),
+            _ => {}
+        }
+    }
+
+    Ok(text)
+}
+
+/// Collect `<item value="..."/>` entries of a `<string-array>`
+fn read_items(reader: &mut Reader<&[u8]>, end: &[u8]) -> Result<Vec<String>, String> {
+    let mut items = Vec::new();
+
+    loop {
+        match reader.read_event().map_err(|e| xml_error(reader, e))? {
+            Event::Empty(e) if e.name().as_ref() == b"item" => {
+                items.push(attribute(&e, b"value")?.unwrap_or_default());
+            }
+            Event::End(e) if e.name().as_ref() == end => break,
+            Event::Eof => return Err("Unexpected end of XML inside array".to_string()),
+            _ => {}
+        }
+    }
+
+    Ok(items)
+}
+
+fn attribute(e: &BytesStart, name: &[u8]) -> Result<Option<String>, String> {
+    let attr = e
+        .try_get_attribute(name)
+        .map_err(|err| format!("Invalid XML attribute: {}", err))?;
+    match attr {
+        Some(a) => a
+            .unescape_value()
+            .map(|v| Some(v.into_owned()))
+            .map_err(|err| format!("Invalid XML attribute value: {}", err)),
+        None => Ok(None),
+    }
+}
+
+fn xml_error(reader: &Reader<&[u8]>, e: quick_xml::Error) -> String {
+    format!("XML error at position {}: {}", reader.buffer_position(), e)
+}
+
+fn get_string(section: &XmlSection, name: &str) -> Option<String> {
+    match section.get(name) {
+        Some(XmlValue::String(s)) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+fn get_int(section: &XmlSection, name: &str) -> Option<i64> {
+    match section.get(name) {
+        Some(XmlValue::Int(i)) => Some(*i),
+        _ => None,
+    }
+}
+
+fn get_bool(section: &XmlSection, name: &str) -> Option<bool> {
+    match section.get(name) {
+        Some(XmlValue::Bool(b)) => Some(*b),
+        _ => None,
+    }
+}
+
+/// Strip the surrounding double quotes Android puts around text values
+pub fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// SSIDs are stored either quoted (`"Name"`) or as unquoted hex bytes
+pub fn decode_ssid(raw: &str) -> String {
+    let raw = raw.trim();
+    if raw.len() >= 2 && raw.starts_with('"') && raw.ends_with('"') {
+        return unquote(raw).to_string();
+    }
+    match decode_hex(raw) {
+        Some(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        None => raw.to_string(),
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse a store holding the one `<Network>` block `network`
+    fn parse_network(network: &str) -> AndroidNetwork {
+        let store = format!(
+            "<?xml version='1.0' encoding='utf-8' standalone='yes' ?>\n\
+             <WifiConfigStoreData>\n<NetworkList>\n<Network>\n{}\n</Network>\n</NetworkList>\n</WifiConfigStoreData>\n",
+            network
+        );
+        let mut networks = parse_str(&store).unwrap();
+        assert_eq!(networks.len(), 1);
+        networks.remove(0)
+    }
+
+    #[test]
+    fn example_store() {
+        let networks = parse_str(include_str!("../../../data/examples/WifiConfigStore.xml")).unwrap();
+        let summary: Vec<(&str, &Security, Option<&str>, i64)> = networks
+            .iter()
+            .map(|n| (n.ssid.as_str(), &n.security, n.psk.as_deref(), n.priority))
+            .collect();
+        assert_eq!(summary, [
+            ("CoffeeShop_WiFi", &Security::WpaPsk, Some("coffee2024"), 1),
+            ("Home_Network", &Security::WpaPsk, Some("MyHomePassword123"), 2),
+            ("Public_Library", &Security::Open, None, 0),
+        ]);
+        assert_eq!(networks[0].randomized_mac.as_deref(), Some("02:00:00:00:00:01"));
+        assert!(networks.iter().all(|n| n.credentials == Credentials::Readable));
+    }
+
+    #[test]
+    fn sae_from_security_params() {
+        let network = parse_network(r#"<WifiConfiguration>
+<string name="ConfigKey">&quot;Lab&quot;SAE</string>
+<string name="SSID">&quot;Lab&quot;</string>
+<string name="PreSharedKey">&quot;lab-password&quot;</string>
+<boolean name="HiddenSSID" value="true" />
+<int name="MeteredOverride" value="1" />
+<int name="MacRandomizationSetting" value="0" />
+<SecurityParamsList>
+<SecurityParams>
+<int name="SecurityType" value="4" />
+<boolean name="IsEnabled" value="true" />
+</SecurityParams>
+</SecurityParamsList>
+</WifiConfiguration>"#);
+        assert_eq!(network.config_key, "\"Lab\"SAE");
+        assert_eq!(network.security, Security::Sae);
+        assert_eq!(network.psk.as_deref(), Some("lab-password"));
+        assert!(network.hidden);
+        assert_eq!(network.metered, Metered::Yes);
+        assert_eq!(network.mac_address, MacAddress::Permanent);
+    }
+
+    #[test]
+    fn sae_added_by_auto_upgrade_is_ignored() {
+        let network = parse_network(r#"<WifiConfiguration>
+<string name="SSID">&quot;Home&quot;</string>
+<string name="PreSharedKey">&quot;password&quot;</string>
+<SecurityParamsList>
+<SecurityParams>
+<int name="SecurityType" value="2" />
+</SecurityParams>
+<SecurityParams>
+<int name="SecurityType" value="4" />
+<boolean name="IsAddedByAutoUpgrade" value="true" />
+</SecurityParams>
+</SecurityParamsList>
+</WifiConfiguration>"#);
+        assert_eq!(network.security, Security::WpaPsk);
+    }
+
+    #[test]
+    fn sae_and_owe_from_key_management() {
+        let key_mgmt = |bits: &str| {
+            parse_network(&format!(
+                "<WifiConfiguration>\n<string name=\"SSID\">&quot;Net&quot;</string>\n\
+                 <byte-array name=\"AllowedKeyManagement\" num=\"2\">{}</byte-array>\n</WifiConfiguration>",
+                bits
+            ))
+            .security
+        };
+        assert_eq!(key_mgmt("0001"), Security::Sae);
+        assert_eq!(key_mgmt("0201"), Security::Transition);
+        assert_eq!(key_mgmt("0002"), Security::Owe);
+        assert_eq!(key_mgmt("01"), Security::Open);
+    }
+
+    #[test]
+    fn wep_keys() {
+        let network = parse_network(r#"<WifiConfiguration>
+<string name="SSID">&quot;Old&quot;</string>
+<byte-array name="AllowedKeyManagement" num="1">01</byte-array>
+<string-array name="WEPKeys" num="4">
+<item value="&quot;abcde&quot;" />
+<item value="" />
+<item value="" />
+<item value="" />
+</string-array>
+</WifiConfiguration>"#);
+        assert_eq!(network.security, Security::Wep);
+        assert_eq!(network.wep_keys, ["abcde"]);
+        assert_eq!(network.psk, None);
+    }
+
+    #[test]
+    fn enterprise_settings() {
+        let network = parse_network(r#"<WifiConfiguration>
+<string name="SSID">&quot;Corp&quot;</string>
+<byte-array name="AllowedKeyManagement" num="1">0c</byte-array>
+</WifiConfiguration>
+<WifiEnterpriseConfiguration>
+<string name="Identity">alice</string>
+<string name="AnonIdentity">anonymous</string>
+<string name="Password">eap-password</string>
+<string name="CaCert">keystore://CACERT_corp</string>
+<string name="DomSuffixMatch">corp.example</string>
+<int name="EapMethod" value="0" />
+<int name="Phase2Method" value="3" />
+</WifiEnterpriseConfiguration>"#);
+        assert_eq!(network.security, Security::Enterprise);
+        let enterprise = network.enterprise.unwrap();
+        assert_eq!(enterprise.eap_method, eap_method::PEAP);
+        assert_eq!(enterprise.phase2_method, phase2_method::MSCHAPV2);
+        assert_eq!(enterprise.identity, "alice");
+        assert_eq!(enterprise.anonymous_identity, "anonymous");
+        assert_eq!(enterprise.password, "eap-password");
+        assert_eq!(enterprise.ca_cert, "keystore://CACERT_corp");
+        assert_eq!(enterprise.domain_suffix_match, "corp.example");
+        assert_eq!(network.credentials, Credentials::Readable);
+    }
+
+    #[test]
+    fn encrypted_data() {
+        let network = parse_network(r#"<WifiConfiguration>
+<string name="SSID">&quot;Secret&quot;</string>
+<PreSharedKey>
+<EncryptedData>
+<byte-array name="EncryptedData" num="4">0a0b0c0d</byte-array>
+<byte-array name="IV" num="2">0102</byte-array>
+</EncryptedData>
+</PreSharedKey>
+</WifiConfiguration>"#);
+        assert_eq!(network.credentials, Credentials::Encrypted);
+        assert_eq!(network.psk, None);
+        assert_eq!(network.security, Security::WpaPsk);
+
+        let network = parse_network(r#"<WifiConfiguration>
+<string name="SSID">&quot;Corp&quot;</string>
+</WifiConfiguration>
+<WifiEnterpriseConfiguration>
+<EncryptedData>
+<byte-array name="EncryptedData" num="4">0a0b0c0d</byte-array>
+</EncryptedData>
+<int name="EapMethod" value="0" />
+</WifiEnterpriseConfiguration>"#);
+        assert_eq!(network.credentials, Credentials::Encrypted);
+        assert_eq!(network.security, Security::Enterprise);
+    }
+
+    #[test]
+    fn hex_ssid() {
+        assert_eq!(decode_ssid("436166c3a9"), "Café");
+        assert_eq!(decode_ssid("\"Café\""), "Café");
+        assert_eq!(decode_ssid("not hex"), "not hex");
+    }
+}
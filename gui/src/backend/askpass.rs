@@ -0,0 +1,115 @@
+//! `sudo -A` runs the GUI binary again as its askpass program. That process
+//! (the shim) passes sudo's prompt to the running GUI over a Unix socket and
+//! prints the password typed into the GUI's dialog.
+
+use std::io::{Read, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use iced::futures::channel::oneshot;
+use iced::futures::{SinkExt, Stream};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixListener;
+
+/// Set for sudo, which passes it on to the askpass program; the GUI binary
+/// started with it acts as the shim
+pub const SOCKET_VAR: &str = "WIFI_MANAGER_ASKPASS_SOCKET";
+
+/// Where the GUI listens for prompts, in the user's runtime directory if there is one
+pub fn socket_path() -> PathBuf {
+    let dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    dir.join(format!("wifi-manager-askpass-{}.sock", std::process::id()))
+}
+
+/// The GUI executable, given to sudo as `SUDO_ASKPASS`
+pub fn shim_path() -> PathBuf {
+    std::env::current_exe().unwrap_or_else(|_| PathBuf::from("wifi-manager-gui"))
+}
+
+/// A password prompt from sudo, waiting for `answer`
+#[derive(Debug, Clone)]
+pub struct AskpassRequest {
+    pub prompt: String,
+    reply: Arc<Mutex<Option<oneshot::Sender<Option<String>>>>>,
+}
+
+impl AskpassRequest {
+    /// Send the password, or `None` to cancel; only the first answer counts
+    pub fn answer(&self, password: Option<String>) {
+        if let Some(reply) = self.reply.lock().ok().and_then(|mut reply| reply.take()) {
+            let _ = reply.send(password);
+        }
+    }
+}
+
+/// Accept the shim's prompts for as long as the GUI runs, one at a time.
+/// Connections from other users are ignored.
+pub fn listen() -> impl Stream<Item = AskpassRequest> {
+    iced::stream::channel(1, |mut output| async move {
+        let path = socket_path();
+        let _ = std::fs::remove_file(&path);
+        let Ok(listener) = UnixListener::bind(&path) else {
+            return;
+        };
+        let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+        // SAFETY: getuid() has no preconditions
+        let uid = unsafe { libc::getuid() };
+
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                continue;
+            };
+            if !stream.peer_cred().is_ok_and(|cred| cred.uid() == uid) {
+                continue;
+            }
+
+            let (reader, mut writer) = stream.into_split();
+            let mut prompt = String::new();
+            if BufReader::new(reader).read_line(&mut prompt).await.is_err() {
+                continue;
+            }
+
+            let (reply, answer) = oneshot::channel();
+            let request = AskpassRequest {
+                prompt: prompt.trim().to_string(),
+                reply: Arc::new(Mutex::new(Some(reply))),
+            };
+            if output.send(request).await.is_err() {
+                break;
+            }
+            // Closing without a line tells the shim the dialog was cancelled
+            if let Ok(Some(password)) = answer.await {
+                let _ = writer.write_all(format!("{}\n", password).as_bytes()).await;
+            }
+        }
+    })
+}
+
+/// Askpass mode: ask the GUI listening on `socket` for the password sudo
+/// prompts for and print it. Returns the exit code, 1 if it was cancelled.
+pub fn run_shim(socket: &Path, prompt: &str) -> i32 {
+    let reply = (|| -> std::io::Result<String> {
+        let mut stream = UnixStream::connect(socket)?;
+        stream.write_all(format!("{}\n", prompt.replace('\n', " ")).as_bytes())?;
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply)?;
+        Ok(reply)
+    })();
+
+    match reply {
+        Ok(reply) => match reply.strip_suffix('\n') {
+            Some(password) => {
+                println!("{}", password);
+                0
+            }
+            None => 1,
+        },
+        Err(e) => {
+            eprintln!("Failed to ask WiFi Manager for the password: {}", e);
+            1
+        }
+    }
+}
@@ -0,0 +1,503 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use crate::backend::helper_protocol::SYSTEM_DIR;
+use crate::backend::privilege::{self, Method};
+use crate::backend::script;
+
+/// Default time limits in seconds of the ADB scripts and of privileged helper
+/// batches (`helper`), overridden by `TIMEOUT_<NAME>` in config.env
+pub const TIMEOUTS: &[(&str, u64)] = &[
+    ("pull-android", 300),
+    ("push-android", 300),
+    ("helper", 300),
+];
+
+/// Directories the CLI works on, resolved from config.env the same way as
+/// `load_config` and `resolve_paths` in wifi-manager.sh
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub base_dir: PathBuf,
+    pub local_dir: PathBuf,
+    pub xml_file: PathBuf,
+    /// `cmd wifi list-networks` output, saved by pull-android without root
+    pub listing_file: PathBuf,
+    pub report_file: PathBuf,
+    pub trash_dir: PathBuf,
+    pub system_dir: PathBuf,
+    /// How the helper runs as root: the `PRIVILEGE_METHOD` tool, or the first
+    /// installed one for `auto`; `None` if there is none
+    pub privilege: Option<Method>,
+    /// Time limit in seconds of each entry of `TIMEOUTS`, 0 = no limit
+    pub timeouts: Vec<(&'static str, u64)>,
+}
+
+/// A variable of config.env that can be edited in the settings view
+pub struct Setting {
+    pub name: &'static str,
+    pub label: &'static str,
+    /// `true`/`false` value, shown as a checkbox
+    pub flag: bool,
+    /// Values offered in a drop-down instead of a text field
+    pub choices: Option<fn() -> Vec<String>>,
+}
+
+pub const SETTINGS: &[Setting] = &[
+    Setting { name: "WIFI_MANAGER_BASE", label: "Data directory", flag: false, choices: None },
+    Setting { name: "LOCAL_DIR_NAME", label: "Connections folder", flag: false, choices: None },
+    Setting { name: "XML_FILENAME", label: "Android XML file", flag: false, choices: None },
+    Setting { name: "REPORT_FILENAME", label: "Report file", flag: false, choices: None },
+    Setting { name: "SYSTEM_DIR", label: "System connections", flag: false, choices: None },
+    Setting { name: "ADB_WIFI_PATH", label: "Device config path", flag: false, choices: None },
+    Setting { name: "ADB_WIFI_PATH_OLD", label: "Device config path (Android 9 and older)", flag: false, choices: None },
+    Setting { name: "ADB_TEMP_PATH", label: "Device temporary file", flag: false, choices: None },
+    Setting { name: "PRIVILEGE_METHOD", label: "Run as root with", flag: false, choices: Some(privilege::choices) },
+    Setting { name: "TIMEOUT_PULL_ANDROID", label: "Pull from Android time limit (seconds, 0 = none)", flag: false, choices: None },
+    Setting { name: "TIMEOUT_PUSH_ANDROID", label: "Push to Android time limit (seconds, 0 = none)", flag: false, choices: None },
+    Setting { name: "TIMEOUT_HELPER", label: "Root helper time limit (seconds, 0 = none)", flag: false, choices: None },
+    Setting { name: "COLOR_OUTPUT", label: "Coloured CLI output", flag: true, choices: None },
+    Setting { name: "VERBOSE", label: "Verbose logging", flag: true, choices: None },
+];
+
+impl Config {
+    /// Evaluate the default config.env, then the user override, on top of the environment
+    pub fn load() -> Self {
+        Self::from_vars(&script_dir(), &load_vars())
+    }
+
+    /// Same as `load`, with the data directory replaced by `base_dir` (a workspace)
+    pub fn with_base(base_dir: &Path) -> Self {
+        let mut vars = load_vars();
+        vars.insert("WIFI_MANAGER_BASE".to_string(), base_dir.to_string_lossy().to_string());
+        Self::from_vars(&script_dir(), &vars)
+    }
+
+    fn from_vars(script_dir: &Path, vars: &HashMap<String, String>) -> Self {
+        let get = |name: &str, default: &str| {
+            vars.get(name)
+                .filter(|v| !v.is_empty())
+                .cloned()
+                .unwrap_or_else(|| default.to_string())
+        };
+
+        // Relative bases are relative to the script, not the working directory
+        let base_dir = script_dir.join(get("WIFI_MANAGER_BASE", "data"));
+
+        Config {
+            local_dir: base_dir.join(get("LOCAL_DIR_NAME", "network_connections")),
+            xml_file: base_dir.join(get("XML_FILENAME", "WifiConfigStore.xml")),
+            listing_file: base_dir.join("android_networks.txt"),
+            report_file: base_dir.join(get("REPORT_FILENAME", "networks_report.md")),
+            trash_dir: base_dir.join(".trash"),
+            system_dir: PathBuf::from(get("SYSTEM_DIR", SYSTEM_DIR)),
+            privilege: privilege::resolve(Method::from_name(&get("PRIVILEGE_METHOD", "auto"))).ok(),
+            timeouts: TIMEOUTS
+                .iter()
+                .map(|&(name, default)| {
+                    let var = format!("TIMEOUT_{}", name.replace('-', "_").to_uppercase());
+                    let secs = vars.get(&var).and_then(|v| v.trim().parse().ok());
+                    (name, secs.unwrap_or(default))
+                })
+                .collect(),
+            base_dir,
+        }
+    }
+
+    /// Time limit for a script command or `helper`, `None` if it has none
+    pub fn command_timeout(&self, name: &str) -> Option<Duration> {
+        let secs = self.timeouts.iter().find(|(n, _)| *n == name).map(|(_, secs)| *secs)?;
+        (secs > 0).then(|| Duration::from_secs(secs))
+    }
+
+    /// A fresh temporary data directory for a test, with the default layout
+    #[cfg(test)]
+    pub fn for_test(name: &str) -> Self {
+        let base_dir = std::env::temp_dir().join(format!("wifi-manager-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&base_dir);
+        fs::create_dir_all(&base_dir).unwrap();
+        Self::at(base_dir)
+    }
+
+    /// Test config for an existing data directory
+    #[cfg(test)]
+    pub fn at(base_dir: PathBuf) -> Self {
+        Config {
+            local_dir: base_dir.join("network_connections"),
+            xml_file: base_dir.join("WifiConfigStore.xml"),
+            listing_file: base_dir.join("android_networks.txt"),
+            report_file: base_dir.join("networks_report.md"),
+            trash_dir: base_dir.join(".trash"),
+            system_dir: base_dir.join("system"),
+            privilege: None,
+            timeouts: Vec::new(),
+            base_dir,
+        }
+    }
+
+    /// Check that the directories exist and the local ones are writable
+    pub fn validate(&self) -> Result<(), String> {
+        check_writable(&self.base_dir)?;
+        if self.local_dir.exists() {
+            check_writable(&self.local_dir)?;
+        }
+        if !self.system_dir.is_dir() {
+            return Err(format!("{} is not a directory", self.system_dir.display()));
+        }
+        Ok(())
+    }
+}
+
+pub fn check_writable(dir: &Path) -> Result<(), String> {
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+    let probe = dir.join(".wifi-manager-write-test");
+    fs::write(&probe, b"").map_err(|e| format!("{} is not writable: {}", dir.display(), e))?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Directory containing wifi-manager.sh and the default config.env
+fn script_dir() -> PathBuf {
+    let script_path = script::get_script_path();
+    let dir = script_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    dir.canonicalize().unwrap_or(dir)
+}
+
+/// All variables as the CLI sees them after `load_config`
+pub fn load_vars() -> HashMap<String, String> {
+    let files: Vec<String> = [Some(script_dir().join("config.env")), user_config_path()]
+        .into_iter()
+        .flatten()
+        .filter_map(|file| fs::read_to_string(file).ok())
+        .collect();
+    source_files(std::env::vars().collect(), &files)
+}
+
+/// Evaluate the contents of the config files in order on top of `env`
+fn source_files(env: HashMap<String, String>, files: &[String]) -> HashMap<String, String> {
+    let env_base = env.get("WIFI_MANAGER_BASE").filter(|b| !b.is_empty()).cloned();
+    let mut vars = env;
+
+    for content in files {
+        evaluate(content, &mut vars);
+    }
+
+    // An exported WIFI_MANAGER_BASE wins over the files, as in load_config
+    if let Some(base) = env_base {
+        vars.insert("WIFI_MANAGER_BASE".to_string(), base);
+    }
+    vars
+}
+
+/// Validate and write `changes` to the user config.env, keeping its comments
+/// and other variables
+pub fn save_user_settings(changes: &[(&str, String)]) -> Result<(), String> {
+    let path = user_config_path().ok_or("HOME is not set")?;
+    let home = std::env::var("HOME").unwrap_or_default();
+
+    // Double quotes do not expand "~", so store the full path
+    let changes: Vec<(&str, String)> = changes
+        .iter()
+        .map(|(name, value)| match value.strip_prefix("~/") {
+            Some(rest) => (*name, format!("{}/{}", home, rest)),
+            None => (*name, value.clone()),
+        })
+        .collect();
+
+    for (name, value) in &changes {
+        if name.starts_with("TIMEOUT_") && !value.is_empty() && value.trim().parse::<u64>().is_err() {
+            return Err(format!("{} must be a number of seconds, got \"{}\"", name, value));
+        }
+    }
+
+    let mut vars = load_vars();
+    for (name, value) in &changes {
+        vars.insert(name.to_string(), value.clone());
+    }
+    Config::from_vars(&script_dir(), &vars).validate()?;
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    fs::write(&path, write_assignments(&content, &changes))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Replace the last assignment of each variable in `content` (the one that wins
+/// when sourced), or append it. Everything else is kept as is.
+fn write_assignments(content: &str, changes: &[(&str, String)]) -> String {
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+
+    for (name, value) in changes {
+        let quoted = double_quote(value);
+        let existing = lines
+            .iter()
+            .rposition(|line| parse_assignment(line).is_some_and(|(n, _)| n == *name));
+
+        match existing {
+            Some(idx) => {
+                // Keep indentation, `export` and a trailing comment
+                let line = &lines[idx];
+                let (_, raw) = parse_assignment(line).unwrap_or_default();
+                let prefix = &line[..line.len() - raw.len()];
+                let (_, word_len) = scan_word(raw, &HashMap::new(), true);
+                let rest: String = raw.chars().skip(word_len).collect();
+                let updated = format!("{}{}{}", prefix, quoted, rest);
+                lines[idx] = updated;
+            }
+            None => lines.push(format!("{}={}", name, quoted)),
+        }
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+fn double_quote(value: &str) -> String {
+    let mut out = String::from('"');
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | '$' | '`') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+    out
+}
+
+/// `~/.config/wifi-manager/config.env`
+pub fn user_config_path() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME")?;
+    Some(PathBuf::from(home).join(".config/wifi-manager/config.env"))
+}
+
+/// Apply the `NAME=value` assignments of a sourced shell file to `vars`.
+/// Supports quoting, `$NAME`, `${NAME}`, `${NAME:-default}` and `${NAME-default}`;
+/// any other line (commands, conditionals) is ignored.
+fn evaluate(content: &str, vars: &mut HashMap<String, String>) {
+    for line in content.lines() {
+        if let Some((name, raw)) = parse_assignment(line) {
+            let value = eval_word(raw, vars, true);
+            vars.insert(name.to_string(), value);
+        }
+    }
+}
+
+/// Split `NAME=value` (optionally prefixed with `export`) into name and raw value
+pub fn parse_assignment(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim_start();
+    let line = line.strip_prefix("export ").map(str::trim_start).unwrap_or(line);
+    let (name, raw) = line.split_once('=')?;
+
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    valid.then_some((name, raw))
+}
+
+/// Unquote and expand a shell word. With `split`, unquoted whitespace or a
+/// comment ends the word.
+fn eval_word(raw: &str, vars: &HashMap<String, String>, split: bool) -> String {
+    scan_word(raw, vars, split).0
+}
+
+/// `eval_word`, also returning how many characters of `raw` the word used
+fn scan_word(raw: &str, vars: &HashMap<String, String>, split: bool) -> (String, usize) {
+    let chars: Vec<char> = raw.chars().collect();
+    let mut out = String::new();
+    let mut quote: Option<char> = None;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match (quote, c) {
+            (Some('\''), '\'') | (Some('"'), '"') => quote = None,
+            (Some('\''), _) => out.push(c),
+            (None, '\'' | '"') => quote = Some(c),
+            (None, c) if split && c.is_whitespace() => break,
+            (None, '~') if i == 0 && matches!(chars.get(1), None | Some('/')) => {
+                out.push_str(vars.get("HOME").map(String::as_str).unwrap_or("~"));
+            }
+            (_, '\\') => {
+                i += 1;
+                if let Some(&next) = chars.get(i) {
+                    out.push(next);
+                }
+            }
+            (_, '$') => {
+                i = expand(&chars, i, vars, &mut out);
+                continue;
+            }
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+
+    (out, i)
+}
+
+/// Expand the parameter starting at `chars[start] == '$'`, returning the index after it
+fn expand(chars: &[char], start: usize, vars: &HashMap<String, String>, out: &mut String) -> usize {
+    let is_name = |c: &char| c.is_ascii_alphanumeric() || *c == '_';
+
+    if chars.get(start + 1) != Some(&'{') {
+        let len = chars[start + 1..].iter().take_while(|c| is_name(c)).count();
+        if len == 0 {
+            out.push('$');
+        } else {
+            let name: String = chars[start + 1..start + 1 + len].iter().collect();
+            out.push_str(vars.get(&name).map(String::as_str).unwrap_or(""));
+        }
+        return start + 1 + len;
+    }
+
+    // Find the matching brace; defaults may contain nested expansions
+    let mut depth = 0;
+    let mut end = chars.len();
+    for (j, c) in chars.iter().enumerate().skip(start + 1) {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    end = j;
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let inner: String = chars[start + 2..end.max(start + 2)].iter().collect();
+    let name_len = inner.chars().take_while(is_name).count();
+    let (name, op) = inner.split_at(name_len);
+    let value = vars.get(name);
+
+    let expanded = if let Some(default) = op.strip_prefix(":-") {
+        match value.filter(|v| !v.is_empty()) {
+            Some(v) => v.clone(),
+            None => eval_word(default, vars, false),
+        }
+    } else if let Some(default) = op.strip_prefix('-') {
+        match value {
+            Some(v) => v.clone(),
+            None => eval_word(default, vars, false),
+        }
+    } else {
+        value.cloned().unwrap_or_default()
+    };
+    out.push_str(&expanded);
+
+    end + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn assignments() {
+        assert_eq!(parse_assignment("NAME=value"), Some(("NAME", "value")));
+        assert_eq!(parse_assignment("  export  NAME=\"a b\""), Some(("NAME", "\"a b\"")));
+        assert_eq!(parse_assignment("_N1=x=y"), Some(("_N1", "x=y")));
+        assert_eq!(parse_assignment("# NAME=value"), None);
+        assert_eq!(parse_assignment("1NAME=value"), None);
+        assert_eq!(parse_assignment("if [ -n \"$X\" ]; then"), None);
+    }
+
+    #[test]
+    fn quoting() {
+        let env = vars(&[("HOME", "/home/u"), ("X", "x")]);
+        assert_eq!(eval_word("'$X \"q\"'", &env, true), "$X \"q\"");
+        assert_eq!(eval_word("\"$X 'q' \\$X\"", &env, true), "x 'q' $X");
+        assert_eq!(eval_word("a\\ b c", &env, true), "a b");
+        assert_eq!(eval_word("value # comment", &env, true), "value");
+        assert_eq!(eval_word("~/data", &env, true), "/home/u/data");
+        assert_eq!(eval_word("\"~/data\"", &env, true), "~/data");
+    }
+
+    #[test]
+    fn parameter_expansion() {
+        let env = vars(&[("SET", "set"), ("EMPTY", "")]);
+        assert_eq!(eval_word("$SET/${SET}x", &env, true), "set/setx");
+        assert_eq!(eval_word("\"${SET:-default}\"", &env, true), "set");
+        assert_eq!(eval_word("\"${EMPTY:-default}\"", &env, true), "default");
+        assert_eq!(eval_word("\"${UNSET:-default}\"", &env, true), "default");
+        assert_eq!(eval_word("\"${EMPTY-default}\"", &env, true), "");
+        assert_eq!(eval_word("\"${UNSET-default}\"", &env, true), "default");
+        assert_eq!(eval_word("\"${UNSET:-${SET}/nested}\"", &env, true), "set/nested");
+        assert_eq!(eval_word("$UNSET", &env, true), "");
+        assert_eq!(eval_word("cost: $", &env, false), "cost: $");
+    }
+
+    #[test]
+    fn environment_and_file_precedence() {
+        let default_file = "WIFI_MANAGER_BASE=\"${WIFI_MANAGER_BASE:-data}\"\n\
+                            SYSTEM_DIR=\"${SYSTEM_DIR:-/etc/NetworkManager/system-connections}\"\n\
+                            VERBOSE=\"${VERBOSE:-false}\"\n";
+        let user_file = "export VERBOSE=true\nWIFI_MANAGER_BASE=/home/u/wifi\n";
+        let env = vars(&[("SYSTEM_DIR", "/tmp/system"), ("WIFI_MANAGER_BASE", "/srv/wifi")]);
+
+        let resolved = source_files(env, &[default_file.to_string(), user_file.to_string()]);
+        // The defaults keep exported values, the user file overrides them,
+        // and an exported WIFI_MANAGER_BASE wins over both
+        assert_eq!(resolved["SYSTEM_DIR"], "/tmp/system");
+        assert_eq!(resolved["VERBOSE"], "true");
+        assert_eq!(resolved["WIFI_MANAGER_BASE"], "/srv/wifi");
+
+        let resolved = source_files(HashMap::new(), &[default_file.to_string(), user_file.to_string()]);
+        assert_eq!(resolved["SYSTEM_DIR"], "/etc/NetworkManager/system-connections");
+        assert_eq!(resolved["WIFI_MANAGER_BASE"], "/home/u/wifi");
+    }
+
+    #[test]
+    fn write_assignments_keeps_the_rest_of_the_file() {
+        let content = "# My overrides\n\
+                       export VERBOSE=false  # noisy otherwise\n\
+                       CUSTOM_THING=\"keep me\"\n\
+                       \n\
+                       VERBOSE=\"false\"\n";
+        let changes = [("VERBOSE", "true".to_string()), ("SYSTEM_DIR", "/srv/$HOME \"x\"".to_string())];
+
+        assert_eq!(
+            write_assignments(content, &changes),
+            "# My overrides\n\
+             export VERBOSE=false  # noisy otherwise\n\
+             CUSTOM_THING=\"keep me\"\n\
+             \n\
+             VERBOSE=\"true\"\n\
+             SYSTEM_DIR=\"/srv/\\$HOME \\\"x\\\"\"\n"
+        );
+
+        // The written value reads back unchanged
+        let mut resolved = HashMap::new();
+        evaluate(&write_assignments(content, &changes), &mut resolved);
+        assert_eq!(resolved["SYSTEM_DIR"], "/srv/$HOME \"x\"");
+        assert_eq!(resolved["CUSTOM_THING"], "keep me");
+    }
+
+    #[test]
+    fn write_assignments_keeps_export_and_comment() {
+        let content = "export VERBOSE=false  # noisy otherwise\n";
+        assert_eq!(
+            write_assignments(content, &[("VERBOSE", "true".to_string())]),
+            "export VERBOSE=\"true\"  # noisy otherwise\n"
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use crate::backend::config::Config;
+use crate::backend::helper;
+use crate::backend::helper_protocol::Request;
+use crate::backend::keyfile::KeyFile;
+
+/// Keys `get_file_hash` in lib/utils.sh leaves out, so they never make
+/// install or backup copy a file
+pub const IGNORED_KEYS: &[&str] = &["uuid", "timestamp"];
+
+/// Keys whose values are masked in the diff view
+const SECRET_KEYS: &[&str] = &[
+    "psk",
+    "password",
+    "private-key-password",
+    "phase2-private-key-password",
+    "wep-key0",
+    "wep-key1",
+    "wep-key2",
+    "wep-key3",
+    "leap-password",
+];
+
+/// How a key compares between the local and the system file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Same,
+    OnlyLocal,
+    OnlySystem,
+    Changed,
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyDiff {
+    pub key: String,
+    pub local: Option<String>,
+    pub system: Option<String>,
+    /// One of `IGNORED_KEYS`
+    pub ignored: bool,
+}
+
+impl KeyDiff {
+    pub fn change(&self) -> Change {
+        match (&self.local, &self.system) {
+            (Some(local), Some(system)) if local == system => Change::Same,
+            (Some(_), Some(_)) => Change::Changed,
+            (Some(_), None) => Change::OnlyLocal,
+            _ => Change::OnlySystem,
+        }
+    }
+
+    pub fn is_secret(&self) -> bool {
+        SECRET_KEYS.contains(&self.key.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SectionDiff {
+    pub name: String,
+    pub keys: Vec<KeyDiff>,
+}
+
+/// A local connection file compared with its counterpart in `SYSTEM_DIR`
+#[derive(Debug, Clone)]
+pub struct FileDiff {
+    pub filename: String,
+    pub local_exists: bool,
+    pub system_exists: bool,
+    pub sections: Vec<SectionDiff>,
+    /// What `get_file_hash` sees: the files differ outside uuid/timestamp lines,
+    /// including order and comments
+    pub hash_differs: bool,
+}
+
+impl FileDiff {
+    /// Compare file contents; `None` when that side does not exist
+    pub fn new(filename: &str, local: Option<&str>, system: Option<&str>) -> Self {
+        let local_doc = KeyFile::parse(local.unwrap_or_default());
+        let system_doc = KeyFile::parse(system.unwrap_or_default());
+
+        FileDiff {
+            filename: filename.to_string(),
+            local_exists: local.is_some(),
+            system_exists: system.is_some(),
+            sections: diff_keyfiles(&local_doc, &system_doc),
+            hash_differs: hashed_lines(local.unwrap_or_default()) != hashed_lines(system.unwrap_or_default()),
+        }
+    }
+
+    /// Number of keys that differ, not counting `IGNORED_KEYS`
+    pub fn changed_keys(&self) -> usize {
+        self.sections
+            .iter()
+            .flat_map(|s| &s.keys)
+            .filter(|k| !k.ignored && k.change() != Change::Same)
+            .count()
+    }
+
+    /// One-line description for the diff view header
+    pub fn summary(&self) -> String {
+        match (self.local_exists, self.system_exists) {
+            (false, false) => "Neither file exists".to_string(),
+            (true, false) => "Not installed: install would add it".to_string(),
+            (false, true) => "Only on the system: backup would pull it".to_string(),
+            _ if !self.hash_differs => "Identical (uuid and timestamp are ignored)".to_string(),
+            _ => match self.changed_keys() {
+                0 => "Only order or comments differ; install or backup would still copy it".to_string(),
+                1 => "1 key differs".to_string(),
+                count => format!("{} keys differ", count),
+            },
+        }
+    }
+}
+
+/// Compare two keyfiles per section and key. Sections and keys keep the
+/// local order, followed by those only the system file has.
+pub fn diff_keyfiles(local: &KeyFile, system: &KeyFile) -> Vec<SectionDiff> {
+    let mut names: Vec<&str> = Vec::new();
+    for name in local.sections().chain(system.sections()) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    names
+        .into_iter()
+        .map(|name| {
+            let local_entries = local.entries(name);
+            let system_entries = system.entries(name);
+
+            let mut keys: Vec<&str> = Vec::new();
+            for (key, _) in local_entries.iter().chain(&system_entries) {
+                if !keys.contains(key) {
+                    keys.push(key);
+                }
+            }
+
+            let value = |entries: &[(&str, &str)], key: &str| {
+                entries.iter().find(|(k, _)| *k == key).map(|(_, v)| v.to_string())
+            };
+            SectionDiff {
+                name: name.to_string(),
+                keys: keys
+                    .into_iter()
+                    .map(|key| KeyDiff {
+                        key: key.to_string(),
+                        local: value(&local_entries, key),
+                        system: value(&system_entries, key),
+                        ignored: IGNORED_KEYS.contains(&key),
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Compare `filename` in the local directory with the installed copy
+pub async fn compare(config: &Config, filename: &str) -> Result<FileDiff, String> {
+    let local_path = config.local_dir.join(filename);
+    let local = match fs::read_to_string(&local_path) {
+        Ok(content) => Some(content),
+        Err(e) if e.kind() == ErrorKind::NotFound => None,
+        Err(e) => return Err(format!("Failed to read {}: {}", local_path.display(), e)),
+    };
+    let system = read_system_files(config, &[filename.to_string()])
+        .await?
+        .remove(filename);
+
+    Ok(FileDiff::new(filename, local.as_deref(), system.as_deref()))
+}
+
+/// Read connection files from `SYSTEM_DIR`, by filename. Files that are not
+/// installed are left out. Root-only files are read with one helper batch.
+pub async fn read_system_files(config: &Config, filenames: &[String]) -> Result<HashMap<String, String>, String> {
+    let mut files = HashMap::new();
+    let mut denied = Vec::new();
+
+    for filename in filenames {
+        match fs::read_to_string(config.system_dir.join(filename)) {
+            Ok(content) => {
+                files.insert(filename.clone(), content);
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) if e.kind() == ErrorKind::PermissionDenied => denied.push(filename.clone()),
+            Err(e) => return Err(format!("Failed to read {}: {}", filename, e)),
+        }
+    }
+
+    if !denied.is_empty() {
+        files.extend(read_privileged(config, denied).await?);
+    }
+    Ok(files)
+}
+
+/// Read every connection file in `SYSTEM_DIR`, by filename. An unreadable
+/// directory is listed by the helper as well.
+pub async fn read_all_system_files(config: &Config) -> Result<HashMap<String, String>, String> {
+    let entries = match fs::read_dir(&config.system_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::PermissionDenied => {
+            let listed = helper::run(config, vec![Request::List]).await?;
+            let filenames = listed.into_iter().next().and_then(|r| r.files).unwrap_or_default();
+            return read_privileged(config, filenames).await;
+        }
+        Err(e) => return Err(format!("Failed to read {}: {}", config.system_dir.display(), e)),
+    };
+
+    let filenames: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.ends_with(".nmconnection"))
+        .collect();
+    read_system_files(config, &filenames).await
+}
+
+/// Read root-only system files through the helper
+async fn read_privileged(config: &Config, filenames: Vec<String>) -> Result<HashMap<String, String>, String> {
+    if filenames.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let requests = filenames.iter().map(|file| Request::Read { file: file.clone() }).collect();
+    let responses = helper::run(config, requests).await?;
+
+    Ok(filenames
+        .into_iter()
+        .zip(responses)
+        .filter_map(|(file, response)| Some((file, response.content?)))
+        .collect())
+}
+
+/// Lines `get_file_hash` hashes
+fn hashed_lines(content: &str) -> Vec<&str> {
+    content
+        .lines()
+        .filter(|line| !line.starts_with("timestamp=") && !line.starts_with("uuid="))
+        .collect()
+}
@@ -0,0 +1,217 @@
+use std::fmt;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::{Output, Stdio};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::{Child, Command};
+use crate::backend::config::Config;
+use crate::backend::helper_protocol::{Batch, BatchResult, Request, Response, SYSTEM_DIR};
+use crate::backend::privilege::{self, PrivilegeError};
+use crate::backend::script;
+
+/// Where `make install`-style setups put the helper; the polkit action names this path
+pub const INSTALLED_PATH: &str = "/usr/local/libexec/wifi-manager-helper";
+
+/// Get the path to the wifi-manager-helper binary
+///
+/// Resolution order:
+/// 1. WIFI_MANAGER_HELPER environment variable
+/// 2. Next to the GUI executable (cargo builds both into the same directory)
+/// 3. `INSTALLED_PATH`
+pub fn helper_path() -> PathBuf {
+    if let Some(path) = std::env::var_os("WIFI_MANAGER_HELPER") {
+        return PathBuf::from(path);
+    }
+    if let Some(path) = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join("wifi-manager-helper")))
+        .filter(|path| path.exists())
+    {
+        return path;
+    }
+    PathBuf::from(INSTALLED_PATH)
+}
+
+/// Why a helper batch failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HelperError {
+    /// The helper could not be started as root
+    Privilege(PrivilegeError),
+    /// A request failed; the helper undid the batch's changes if `rolled_back`
+    Batch { error: String, rolled_back: bool },
+    /// Sending the batch or reading the result failed
+    Failed(String),
+}
+
+impl fmt::Display for HelperError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HelperError::Privilege(e) => write!(f, "{}", e),
+            HelperError::Batch { error, rolled_back: true } => write!(f, "{} (changes were rolled back)", error),
+            HelperError::Batch { error, rolled_back: false } => write!(f, "{}", error),
+            HelperError::Failed(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<HelperError> for String {
+    fn from(e: HelperError) -> Self {
+        e.to_string()
+    }
+}
+
+/// The helper always works on `SYSTEM_DIR`, so refuse to run it for another
+/// configured directory rather than change files the views did not show
+pub fn check_system_dir(config: &Config) -> Result<(), HelperError> {
+    if config.system_dir == Path::new(SYSTEM_DIR) {
+        return Ok(());
+    }
+    Err(HelperError::Failed(format!(
+        "SYSTEM_DIR is set to {}, but the privileged helper only manages {}; reset SYSTEM_DIR in Settings",
+        config.system_dir.display(),
+        SYSTEM_DIR
+    )))
+}
+
+/// Run `requests` as one transaction in the helper, through the configured
+/// privilege method unless the GUI already runs as root. Returns one response
+/// per request; on failure the helper has already undone the batch's changes.
+pub async fn run(config: &Config, requests: Vec<Request>) -> Result<Vec<Response>, HelperError> {
+    check_system_dir(config)?;
+    let count = requests.len();
+    let input = serde_json::to_string(&Batch { requests })
+        .map_err(|e| HelperError::Failed(format!("Failed to encode helper request: {}", e)))?;
+
+    let helper = helper_path();
+    // SAFETY: geteuid() has no preconditions
+    let method = if unsafe { libc::geteuid() } == 0 {
+        None
+    } else {
+        Some(config.privilege.ok_or(HelperError::Privilege(PrivilegeError::NoneInstalled))?)
+    };
+    let mut command = match method {
+        Some(method) => method.command(&helper),
+        None => Command::new(&helper),
+    };
+    let mut child = match command
+        // Own process group, so cancelling also reaches the helper below sudo/pkexec
+        .process_group(0)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => return Err(match method {
+            Some(method) if e.kind() == ErrorKind::NotFound => {
+                HelperError::Privilege(PrivilegeError::NotInstalled(method))
+            }
+            Some(method) => HelperError::Failed(format!("Failed to start {}: {}", method, e)),
+            None => HelperError::Failed(format!("Failed to start {}: {}", helper.display(), e)),
+        }),
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes()).await
+            .map_err(|e| HelperError::Failed(format!("Failed to send helper request: {}", e)))?;
+    }
+
+    let (stdout, stderr) = (child.stdout.take(), child.stderr.take());
+    let mut running = Running(Some(child));
+    let finished = async {
+        let (stdout, stderr, status) = iced::futures::join!(read_all(stdout), read_all(stderr), running.wait());
+        status.map(|status| Output { status, stdout, stderr })
+    };
+    let output = match config.command_timeout("helper") {
+        Some(limit) => tokio::time::timeout(limit, finished)
+            .await
+            .map_err(|_| HelperError::Failed("Privileged helper timed out".to_string()))?,
+        None => finished.await,
+    }
+    .map_err(|e| HelperError::Failed(format!("Failed to wait for helper: {}", e)))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let Ok(result) = serde_json::from_str::<BatchResult>(stdout.trim()) else {
+        // The helper never ran: dismissed dialog, not authorised, helper missing...
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(match method {
+            Some(method) => HelperError::Privilege(privilege::classify(method, output.status, &stderr)),
+            None => HelperError::Failed(match stderr.lines().rfind(|l| !l.trim().is_empty()) {
+                Some(line) => format!("Privileged helper failed: {}", line.trim()),
+                None => format!("Privileged helper failed ({})", output.status),
+            }),
+        });
+    };
+
+    match result.error {
+        Some(error) => Err(HelperError::Batch { error, rolled_back: result.rolled_back }),
+        None if result.responses.len() != count => {
+            Err(HelperError::Failed("Privileged helper skipped requests".to_string()))
+        }
+        None => Ok(result.responses),
+    }
+}
+
+/// A started helper. Dropping it before the helper exited (the batch timed out
+/// or its task was aborted from the GUI) stops the helper's process group.
+struct Running(Option<Child>);
+
+impl Running {
+    async fn wait(&mut self) -> std::io::Result<std::process::ExitStatus> {
+        match self.0.as_mut() {
+            Some(child) => child.wait().await,
+            None => Err(std::io::Error::other("helper already stopped")),
+        }
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        let Some(mut child) = self.0.take() else {
+            return;
+        };
+        // An exited child has no id, so terminate() returns at once
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move { script::terminate(&mut child).await });
+        }
+    }
+}
+
+/// Everything written to a pipe until it closes
+async fn read_all(pipe: Option<impl AsyncRead + Unpin>) -> Vec<u8> {
+    let mut buf = Vec::new();
+    if let Some(mut pipe) = pipe {
+        let _ = pipe.read_to_end(&mut buf).await;
+    }
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn dropping_a_running_helper_stops_its_process_group() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let child = Command::new("sh")
+                .args(["-c", "sleep 30 & wait"])
+                .process_group(0)
+                .spawn()
+                .unwrap();
+            let group = child.id().unwrap() as libc::pid_t;
+            drop(Running(Some(child)));
+
+            // SAFETY: kill() with signal 0 only checks whether the group exists
+            let alive = || unsafe { libc::kill(-group, 0) } == 0;
+            for _ in 0..50 {
+                if !alive() {
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            panic!("process group {} still running", group);
+        });
+    }
+}
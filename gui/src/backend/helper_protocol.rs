@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+/// The only directory the helper manages when started through a privilege tool
+pub const SYSTEM_DIR: &str = "/etc/NetworkManager/system-connections";
+
+/// One operation of the privileged helper, on a connection file directly inside
+/// the system connections directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Request {
+    /// Filenames of all connection files
+    List,
+    Read { file: String },
+    /// Replace the file atomically, `0600 root:root`
+    Write { file: String, content: String },
+    Delete { file: String },
+    /// `nmcli connection reload`, then check every file written so far was loaded
+    Reload,
+}
+
+/// Requests run in order as one transaction: the first failure stops the
+/// batch and undoes its writes and deletes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Batch {
+    pub requests: Vec<Request>,
+}
+
+/// Outcome of one request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Response {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// `list`: connection filenames, sorted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub files: Option<Vec<String>>,
+    /// `read`: file content, `None` when the file is not installed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// `write`/`delete`: whether the file existed before
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub existed: Option<bool>,
+}
+
+/// What the helper prints: one response per request that ran
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub responses: Vec<Response>,
+    /// A request failed and the batch's changes were undone
+    #[serde(default)]
+    pub rolled_back: bool,
+    /// Why the batch failed, including an unreadable request or a failed rollback
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
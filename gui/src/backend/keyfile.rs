@@ -0,0 +1,351 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// One physical line of a keyfile
+#[derive(Debug, Clone, PartialEq)]
+enum Line {
+    /// Blank lines, comments and anything that is not a section or entry
+    Other(String),
+    Section { name: String, raw: String },
+    /// `value` is unescaped; `raw` is kept until the entry is modified, so
+    /// untouched lines serialize unchanged
+    Entry { key: String, value: String, raw: Option<String> },
+}
+
+/// An INI/keyfile document (`.nmconnection`) that preserves section order,
+/// unknown keys, comments and blank lines. Values are unescaped on read and
+/// escaped on write the way GLib keyfiles are.
+///
+/// Serializing a document that was not modified yields the original bytes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeyFile {
+    lines: Vec<Line>,
+    trailing_newline: bool,
+}
+
+impl KeyFile {
+    /// Parse keyfile content
+    pub fn parse(content: &str) -> Self {
+        let trailing_newline = content.ends_with('\n');
+        let body = content.strip_suffix('\n').unwrap_or(content);
+
+        let lines = if content.is_empty() {
+            Vec::new()
+        } else {
+            body.split('\n').map(parse_line).collect()
+        };
+
+        Self { lines, trailing_newline }
+    }
+
+    /// Load and parse a keyfile from disk
+    pub fn load(path: &Path) -> Result<Self, String> {
+        fs::read_to_string(path)
+            .map(|content| Self::parse(&content))
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+    }
+
+    /// Write the document to disk
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_string())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Get the value of `key` in `section`
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.find_entry(section, key).map(|idx| match &self.lines[idx] {
+            Line::Entry { value, .. } => value.as_str(),
+            _ => unreachable!(),
+        })
+    }
+
+    /// Set `key` in `section`, creating the entry or section if needed.
+    /// New entries go after the last entry of the section; new sections are appended.
+    pub fn set(&mut self, section: &str, key: &str, value: &str) {
+        if let Some(idx) = self.find_entry(section, key) {
+            if let Line::Entry { value: old, raw, .. } = &mut self.lines[idx] {
+                if old != value {
+                    *old = value.to_string();
+                    *raw = None;
+                }
+            }
+            return;
+        }
+
+        let entry = Line::Entry {
+            key: key.to_string(),
+            value: value.to_string(),
+            raw: None,
+        };
+
+        match self.section_range(section) {
+            Some((start, end)) => {
+                // Insert after the last non-blank line so the section's trailing
+                // blank separator stays in place
+                let mut insert_at = end;
+                while insert_at > start + 1 && matches!(&self.lines[insert_at - 1], Line::Other(l) if l.trim().is_empty()) {
+                    insert_at -= 1;
+                }
+                self.lines.insert(insert_at, entry);
+            }
+            None => {
+                if self.lines.last().is_some_and(|l| !matches!(l, Line::Other(raw) if raw.trim().is_empty())) {
+                    self.lines.push(Line::Other(String::new()));
+                }
+                self.lines.push(Line::Section {
+                    name: section.to_string(),
+                    raw: format!("[{}]", section),
+                });
+                self.lines.push(entry);
+                self.trailing_newline = true;
+            }
+        }
+    }
+
+    /// Names of all sections, in file order
+    pub fn sections(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().filter_map(|l| match l {
+            Line::Section { name, .. } => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Key/value pairs of `section`, in file order
+    pub fn entries(&self, section: &str) -> Vec<(&str, &str)> {
+        let Some((start, end)) = self.section_range(section) else {
+            return Vec::new();
+        };
+        self.lines[start + 1..end]
+            .iter()
+            .filter_map(|l| match l {
+                Line::Entry { key, value, .. } => Some((key.as_str(), value.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Remove `key` from `section`, returning its previous value
+    pub fn remove(&mut self, section: &str, key: &str) -> Option<String> {
+        let idx = self.find_entry(section, key)?;
+        match self.lines.remove(idx) {
+            Line::Entry { value, .. } => Some(value),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Remove a whole section including its entries and trailing blank lines
+    pub fn remove_section(&mut self, section: &str) -> bool {
+        match self.section_range(section) {
+            Some((start, end)) => {
+                self.lines.drain(start..end);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Index of the entry line for `key` in `section`
+    fn find_entry(&self, section: &str, key: &str) -> Option<usize> {
+        let (start, end) = self.section_range(section)?;
+        (start + 1..end).find(|&idx| matches!(&self.lines[idx], Line::Entry { key: k, .. } if k == key))
+    }
+
+    /// Line range `[header, next header)` of the first section named `section`
+    fn section_range(&self, section: &str) -> Option<(usize, usize)> {
+        let start = self
+            .lines
+            .iter()
+            .position(|l| matches!(l, Line::Section { name, .. } if name == section))?;
+        let end = self.lines[start + 1..]
+            .iter()
+            .position(|l| matches!(l, Line::Section { .. }))
+            .map_or(self.lines.len(), |offset| start + 1 + offset);
+        Some((start, end))
+    }
+}
+
+impl fmt::Display for KeyFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, line) in self.lines.iter().enumerate() {
+            if idx > 0 {
+                f.write_str("\n")?;
+            }
+            match line {
+                Line::Other(raw) | Line::Section { raw, .. } => f.write_str(raw)?,
+                Line::Entry { raw: Some(raw), .. } => f.write_str(raw)?,
+                Line::Entry { key, value, raw: None } => write!(f, "{}={}", key, escape(value))?,
+            }
+        }
+        if self.trailing_newline && !self.lines.is_empty() {
+            f.write_str("\n")?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_line(raw: &str) -> Line {
+    let trimmed = raw.trim();
+
+    // GLib keyfiles only have `#` comments
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return Line::Other(raw.to_string());
+    }
+
+    if trimmed.starts_with('[') && trimmed.ends_with(']') {
+        return Line::Section {
+            name: trimmed[1..trimmed.len() - 1].to_string(),
+            raw: raw.to_string(),
+        };
+    }
+
+    match trimmed.split_once('=') {
+        Some((key, value)) => Line::Entry {
+            key: key.trim_end().to_string(),
+            // A CRLF file leaves `\r` at the end of the line; it is kept in `raw` only
+            value: unescape(value.trim_start().strip_suffix('\r').unwrap_or(value.trim_start())),
+            raw: Some(raw.to_string()),
+        },
+        None => Line::Other(raw.to_string()),
+    }
+}
+
+/// Decode `\s`, `\n`, `\t`, `\r`, `\\` and `\;`; other sequences are kept as written
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => out.push(' '),
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some('\\') => out.push('\\'),
+            Some(';') => out.push(';'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Encode a value like `g_key_file_set_string`: leading spaces as `\s`, and
+/// backslashes and control characters escaped
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut leading = true;
+    for c in value.chars() {
+        match c {
+            ' ' if leading => out.push_str("\\s"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            '\\' => out.push_str("\\\\"),
+            c => out.push(c),
+        }
+        leading &= c == ' ';
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILE: &str = "\
+# Written by hand
+[connection]
+id=Home
+uuid=0b3c6f7e-1111-2222-3333-444455556666
+type=wifi
+x-unknown-key=kept as is
+
+[wifi]
+ssid=Home
+
+# trailing comment
+
+[wifi-security]
+key-mgmt=wpa-psk
+psk=a\\sb\\\\c
+
+[ipv4]
+method=auto
+[ipv4]
+method=manual
+";
+
+    #[test]
+    fn unchanged_document_round_trips() {
+        assert_eq!(KeyFile::parse(PROFILE).to_string(), PROFILE);
+        assert_eq!(KeyFile::parse("").to_string(), "");
+        assert_eq!(KeyFile::parse("[a]\nk=v").to_string(), "[a]\nk=v");
+        let crlf = PROFILE.replace('\n', "\r\n");
+        assert_eq!(KeyFile::parse(&crlf).to_string(), crlf);
+    }
+
+    #[test]
+    fn comments_blank_lines_and_unknown_keys() {
+        let doc = KeyFile::parse(PROFILE);
+        assert_eq!(doc.get("connection", "x-unknown-key"), Some("kept as is"));
+        assert_eq!(doc.sections().collect::<Vec<_>>(), ["connection", "wifi", "wifi-security", "ipv4", "ipv4"]);
+        assert_eq!(doc.entries("wifi"), [("ssid", "Home")]);
+
+        // Only `#` starts a comment
+        let doc = KeyFile::parse("[a]\n;key=value\n#key=other\n");
+        assert_eq!(doc.get("a", ";key"), Some("value"));
+        assert_eq!(doc.get("a", "#key"), None);
+    }
+
+    #[test]
+    fn duplicate_sections_use_the_first() {
+        let mut doc = KeyFile::parse(PROFILE);
+        assert_eq!(doc.get("ipv4", "method"), Some("auto"));
+        doc.set("ipv4", "method", "disabled");
+        assert!(doc.to_string().contains("[ipv4]\nmethod=disabled\n[ipv4]\nmethod=manual\n"));
+    }
+
+    #[test]
+    fn values_are_unescaped() {
+        let doc = KeyFile::parse(PROFILE);
+        assert_eq!(doc.get("wifi-security", "psk"), Some("a b\\c"));
+
+        let doc = KeyFile::parse("[a]\nk=\\sx\\ny\\;z\\q\r\n");
+        assert_eq!(doc.get("a", "k"), Some(" x\ny;z\\q"));
+    }
+
+    #[test]
+    fn set_and_remove() {
+        let mut doc = KeyFile::parse(PROFILE);
+        doc.set("wifi", "hidden", "true");
+        doc.set("wifi-security", "psk", " new\\pass");
+        doc.set("proxy", "method", "none");
+        assert_eq!(doc.remove("connection", "x-unknown-key").as_deref(), Some("kept as is"));
+        assert_eq!(doc.remove("connection", "missing"), None);
+
+        let text = doc.to_string();
+        // After the section's last non-blank line, before the blank separator
+        assert!(text.contains("# trailing comment\nhidden=true\n\n[wifi-security]"));
+        assert!(text.contains("psk=\\snew\\\\pass\n"));
+        assert!(text.ends_with("method=manual\n\n[proxy]\nmethod=none\n"));
+        assert!(!text.contains("x-unknown-key"));
+
+        let reparsed = KeyFile::parse(&text);
+        assert_eq!(reparsed.get("wifi-security", "psk"), Some(" new\\pass"));
+
+        // Setting an unchanged value keeps the original line
+        let mut doc = KeyFile::parse(PROFILE);
+        doc.set("wifi-security", "psk", "a b\\c");
+        assert_eq!(doc.to_string(), PROFILE);
+
+        assert!(doc.remove_section("wifi"));
+        assert!(!doc.to_string().contains("ssid=Home"));
+    }
+}
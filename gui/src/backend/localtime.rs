@@ -0,0 +1,26 @@
+/// `timestamp` (seconds since the epoch) in local time as `YYYY-MM-DD HH:MM:SS`,
+/// like `date '+%Y-%m-%d %H:%M:%S'`
+pub fn format(timestamp: i64) -> String {
+    let time = timestamp as libc::time_t;
+    // SAFETY: localtime_r only reads `time` and writes to the tm we own
+    let tm = unsafe {
+        let mut tm: libc::tm = std::mem::zeroed();
+        libc::localtime_r(&time, &mut tm);
+        tm
+    };
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
+
+/// The current local time, as `format` writes it
+pub fn now() -> String {
+    // SAFETY: time() accepts a null pointer
+    format(unsafe { libc::time(std::ptr::null_mut()) } as i64)
+}
@@ -1,2 +1,19 @@
+pub mod android_export;
+pub mod android_import;
+pub mod android_listing;
+pub mod android_xml;
+pub mod askpass;
+pub mod config;
+pub mod diff;
+pub mod helper;
+pub mod helper_protocol;
+pub mod keyfile;
+pub mod localtime;
 pub mod nmconnection;
+pub mod plan;
+pub mod privilege;
+pub mod report;
 pub mod script;
+pub mod sync;
+pub mod trash;
+pub mod workspace;
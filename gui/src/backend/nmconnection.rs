@@ -1,52 +1,65 @@
 use std::fs;
-use std::path::Path;
-use crate::models::network::Network;
+use std::path::{Path, PathBuf};
+use crate::backend::config::Config;
+use crate::backend::keyfile::KeyFile;
+use crate::backend::report;
+use crate::models::network::{EnterpriseSettings, MacAddress, Metered, Network, NetworkSettings, Security};
 use uuid::Uuid;
 
 /// Parse a single .nmconnection file
 pub fn parse_file(path: &Path) -> Option<Network> {
-    let content = fs::read_to_string(path).ok()?;
+    let doc = KeyFile::load(path).ok()?;
     
-    let mut ssid = String::new();
-    let mut security = String::from("Unknown");
-    let mut password = None;
-    let mut section = String::new();
-    
-    for line in content.lines() {
-        let line = line.trim();
-        
-        if line.starts_with('[') && line.ends_with(']') {
-            section = line[1..line.len()-1].to_string();
-            continue;
-        }
-        
-        if let Some((key, value)) = line.split_once('=') {
-            match (section.as_str(), key) {
-                ("wifi", "ssid") => ssid = value.to_string(),
-                ("connection", "id") if ssid.is_empty() => ssid = value.to_string(),
-                ("wifi-security", "key-mgmt") => {
-                    security = match value {
-                        v if v.contains("wpa") => "WPA/WPA2".to_string(),
-                        v if v.contains("sae") => "WPA3".to_string(),
-                        _ => "Unknown".to_string(),
-                    };
-                }
-                ("wifi-security", "psk") => password = Some(value.to_string()),
-                _ => {}
-            }
-        }
-    }
+    let ssid = doc.get("wifi", "ssid")
+        .filter(|s| !s.is_empty())
+        .or_else(|| doc.get("connection", "id"))
+        .unwrap_or_default()
+        .to_string();
     
     if ssid.is_empty() {
         return None;
     }
     
-    // If no security section but no password, it's open
-    if password.is_none() && security == "Unknown" {
-        security = "Open".to_string();
+    let security = Security::detect(|key| doc.get("wifi-security", key));
+    let password = match security {
+        Security::Wep => doc.get("wifi-security", "wep-key0"),
+        Security::Enterprise => doc.get("802-1x", "password"),
+        _ => doc.get("wifi-security", "psk"),
     }
+    .map(|p| p.to_string());
     
-    Some(Network::new(ssid, security, password))
+    let network = Network::new(ssid, security, password).with_path(path.to_path_buf());
+    if network.security == Security::Enterprise {
+        Some(network.with_enterprise(parse_enterprise(&doc)))
+    } else {
+        Some(network)
+    }
+}
+
+/// Read the `[802-1x]` section of an enterprise profile
+fn parse_enterprise(doc: &KeyFile) -> EnterpriseSettings {
+    let get = |key: &str| doc.get("802-1x", key).unwrap_or_default().to_string();
+    // Certificates may be written as plain paths or file:// URIs
+    let get_path = |key: &str| {
+        let value = doc.get("802-1x", key).unwrap_or_default();
+        value.strip_prefix("file://").unwrap_or(value).to_string()
+    };
+    
+    EnterpriseSettings {
+        // eap is a list ("peap;"), the first entry is the one in use
+        eap: doc.get("802-1x", "eap")
+            .and_then(|list| list.split(';').find(|m| !m.is_empty()))
+            .unwrap_or_default()
+            .to_string(),
+        identity: get("identity"),
+        anonymous_identity: get("anonymous-identity"),
+        phase2_auth: get("phase2-auth"),
+        ca_cert: get_path("ca-cert"),
+        client_cert: get_path("client-cert"),
+        private_key: get_path("private-key"),
+        private_key_password: get("private-key-password"),
+        domain_suffix_match: get("domain-suffix-match"),
+    }
 }
 
 /// Load all networks from a directory
@@ -56,7 +69,7 @@ pub fn load_networks(dir: &Path) -> Vec<Network> {
     if let Ok(entries) = fs::read_dir(dir) {
         for entry in entries.flatten() {
             let path = entry.path();
-            if path.extension().map_or(false, |e| e == "nmconnection") {
+            if path.extension().is_some_and(|e| e == "nmconnection") {
                 if let Some(network) = parse_file(&path) {
                     networks.push(network);
                 }
@@ -64,62 +77,159 @@ pub fn load_networks(dir: &Path) -> Vec<Network> {
         }
     }
     
-    networks.sort_by(|a, b| a.ssid.to_lowercase().cmp(&b.ssid.to_lowercase()));
+    networks.sort_by_key(|n| n.ssid.to_lowercase());
     networks
 }
 
-/// Check if report and nmconnection files are in sync
-pub fn check_sync_status(data_dir: &Path) -> (bool, usize) {
-    let connections_dir = data_dir.join("network_connections");
-    let report_file = data_dir.join("networks_report.md");
-    
-    let nmconnection_count = fs::read_dir(&connections_dir)
-        .map(|entries| {
-            entries
-                .flatten()
-                .filter(|e| e.path().extension().map_or(false, |ext| ext == "nmconnection"))
-                .count()
-        })
-        .unwrap_or(0);
-    
-    // Simple sync check: report exists and has same network count
-    let report_count = if report_file.exists() {
-        fs::read_to_string(&report_file)
-            .map(|content| {
-                content.lines()
-                    .filter(|line| line.starts_with('|') && !line.contains("Network Name") && !line.contains("---"))
-                    .count()
-            })
-            .unwrap_or(0)
+/// Create a new .nmconnection file, or update the credentials of an existing one.
+/// `enterprise` is only used when `security` is `Enterprise`.
+pub fn create_network(
+    config: &Config,
+    ssid: &str,
+    password: &str,
+    security: &Security,
+    enterprise: &EnterpriseSettings,
+) -> Result<(), String> {
+    let connections_dir = &config.local_dir;
+    
+    // Create directory if it doesn't exist
+    fs::create_dir_all(connections_dir)
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    
+    let filepath = connections_dir.join(connection_filename(ssid));
+    
+    // Keep everything else (uuid, IP settings, BSSID pins...) of an existing profile
+    let mut doc = if filepath.exists() {
+        KeyFile::load(&filepath)?
+    } else {
+        KeyFile::parse(&new_profile_template(ssid))
+    };
+    
+    let enterprise = if *security == Security::Enterprise {
+        import_certificates(connections_dir, ssid, enterprise)?
     } else {
-        0
+        EnterpriseSettings::default()
     };
     
-    (nmconnection_count == report_count && nmconnection_count > 0, nmconnection_count)
+    doc.set("connection", "id", ssid);
+    doc.set("wifi", "ssid", ssid);
+    write_security(&mut doc, security, password, &enterprise);
+    
+    doc.save(&filepath)
 }
 
-/// Create a new .nmconnection file
-pub fn create_network(data_dir: &Path, ssid: &str, password: &str, security: &str) -> Result<(), String> {
-    let connections_dir = data_dir.join("network_connections");
+/// Copy the certificate and key files referenced by `enterprise` into
+/// `network_connections/certs/<ssid>/` and point the settings at the copies,
+/// so the profile keeps working when the originals move.
+fn import_certificates(
+    connections_dir: &Path,
+    ssid: &str,
+    enterprise: &EnterpriseSettings,
+) -> Result<EnterpriseSettings, String> {
+    let cert_dir = cert_dir(connections_dir, ssid);
+    let mut imported = enterprise.clone();
     
-    // Create directory if it doesn't exist
-    fs::create_dir_all(&connections_dir)
-        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    for (field, private) in [
+        (&mut imported.ca_cert, false),
+        (&mut imported.client_cert, false),
+        (&mut imported.private_key, true),
+    ] {
+        if field.is_empty() {
+            continue;
+        }
+        let source = PathBuf::from(field.as_str());
+        // Imported paths are canonical, while cert_dir may go through a symlink
+        let canonical_source = source.canonicalize().unwrap_or_else(|_| source.clone());
+        let canonical_dir = cert_dir.canonicalize().unwrap_or_else(|_| cert_dir.clone());
+        if source.starts_with(&cert_dir) || canonical_source.starts_with(&canonical_dir) {
+            continue;
+        }
+        let name = source.file_name()
+            .ok_or_else(|| format!("Invalid certificate path: {}", field))?;
+        
+        fs::create_dir_all(&cert_dir)
+            .map_err(|e| format!("Failed to create certificate directory: {}", e))?;
+        let target = cert_dir.join(name);
+        // Copying a file onto itself would truncate it
+        if target.canonicalize().ok() != Some(canonical_source) {
+            fs::copy(&source, &target)
+                .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))?;
+        }
+        
+        if private {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&target, fs::Permissions::from_mode(0o600))
+                .map_err(|e| format!("Failed to set key permissions: {}", e))?;
+        }
+        
+        let target = target.canonicalize().unwrap_or(target);
+        *field = target.to_string_lossy().to_string();
+    }
     
-    // Sanitize filename
-    let safe_name: String = ssid.chars()
-        .map(|c| if "<>:\"/\\|?*".contains(c) { '_' } else { c })
-        .collect();
+    Ok(imported)
+}
+
+/// Directory of the certificates imported for `ssid`, `certs/<filename stem>`
+fn cert_dir(connections_dir: &Path, ssid: &str) -> PathBuf {
+    let filename = connection_filename(ssid);
+    connections_dir
+        .join("certs")
+        .join(filename.trim_end_matches(".nmconnection"))
+}
+
+/// After a rename, move the certificates imported for `old_ssid` to the directory
+/// of `new_ssid` and point the `[802-1x]` paths and `enterprise` at the moved files
+fn move_certificates(
+    connections_dir: &Path,
+    old_ssid: &str,
+    new_ssid: &str,
+    doc: &mut KeyFile,
+    enterprise: &mut EnterpriseSettings,
+) -> Result<(), String> {
+    let old_dir = cert_dir(connections_dir, old_ssid);
+    let new_dir = cert_dir(connections_dir, new_ssid);
+    if old_dir == new_dir || !old_dir.is_dir() {
+        return Ok(());
+    }
+    if new_dir.exists() {
+        return Err(format!("A certificate directory for '{}' already exists", new_ssid));
+    }
     
-    let filepath = connections_dir.join(format!("{}.nmconnection", safe_name));
+    // Imported paths are canonical, but the file may also use the plain one
+    let old_prefixes = [old_dir.canonicalize().unwrap_or_else(|_| old_dir.clone()), old_dir.clone()];
+    fs::rename(&old_dir, &new_dir)
+        .map_err(|e| format!("Failed to move certificate directory: {}", e))?;
+    let new_dir = new_dir.canonicalize().unwrap_or(new_dir);
     
+    let relocate = |value: &str| -> Option<String> {
+        let path = Path::new(value.strip_prefix("file://").unwrap_or(value));
+        let rest = old_prefixes.iter().find_map(|old| path.strip_prefix(old).ok())?;
+        Some(new_dir.join(rest).to_string_lossy().to_string())
+    };
+    
+    for key in ["ca-cert", "client-cert", "private-key"] {
+        if let Some(moved) = doc.get("802-1x", key).and_then(relocate) {
+            doc.set("802-1x", key, &moved);
+        }
+    }
+    for field in [&mut enterprise.ca_cert, &mut enterprise.client_cert, &mut enterprise.private_key] {
+        if let Some(moved) = relocate(field) {
+            *field = moved;
+        }
+    }
+    Ok(())
+}
+
+/// Default profile, same layout as `create_nmconnection` in lib/nmconnection.sh.
+/// The `[wifi-security]` section is filled (or removed) by `write_security`.
+fn new_profile_template(ssid: &str) -> String {
     let uuid = Uuid::new_v4();
     let timestamp = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
         .as_micros();
     
-    let mut content = format!(
+    format!(
         r#"[connection]
 id={ssid}
 uuid={uuid}
@@ -132,27 +242,10 @@ mode=infrastructure
 ssid={ssid}
 hidden=false
 
-"#
-    );
-    
-    // Add security section if password provided
-    if !password.is_empty() && security != "Open" {
-        let key_mgmt = match security {
-            "WPA3" => "sae",
-            _ => "wpa-psk",
-        };
-        content.push_str(&format!(
-            r#"[wifi-security]
+[wifi-security]
 auth-alg=open
-key-mgmt={key_mgmt}
-psk={password}
 
-"#
-        ));
-    }
-    
-    content.push_str(
-        r#"[ipv4]
+[ipv4]
 method=auto
 dns-search=
 
@@ -163,10 +256,321 @@ dns-search=
 
 [proxy]
 "#
-    );
+    )
+}
+
+/// Write the `[wifi-security]` keys for `security`, removing keys of other modes.
+/// Modes that need a key fall back to Open when `password` is empty; `Unknown`
+/// leaves the file's security sections untouched.
+fn write_security(doc: &mut KeyFile, security: &Security, password: &str, enterprise: &EnterpriseSettings) {
+    if let Security::Unknown(_) = security {
+        return;
+    }
     
-    fs::write(&filepath, content)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
+    if *security == Security::Enterprise {
+        write_enterprise(doc, enterprise, password);
+    } else {
+        doc.remove_section("802-1x");
+    }
     
-    Ok(())
+    if *security == Security::Open || (security.requires_password() && password.is_empty()) {
+        doc.remove_section("wifi-security");
+        return;
+    }
+    
+    if doc.get("wifi-security", "auth-alg").is_none() {
+        doc.set("wifi-security", "auth-alg", "open");
+    }
+    if let Some(key_mgmt) = security.key_mgmt() {
+        doc.set("wifi-security", "key-mgmt", key_mgmt);
+    }
+    
+    match security {
+        Security::Wep => {
+            doc.set("wifi-security", "wep-key0", password);
+            doc.set("wifi-security", "wep-key-type", wep_key_type(password));
+            doc.remove("wifi-security", "psk");
+        }
+        Security::WpaPsk | Security::Transition | Security::Sae => {
+            doc.set("wifi-security", "psk", password);
+            // An agent-owned secret flag would make NetworkManager ignore the stored psk
+            doc.remove("wifi-security", "psk-flags");
+            for key in ["wep-key0", "wep-key-type"] {
+                doc.remove("wifi-security", key);
+            }
+        }
+        _ => {
+            for key in ["psk", "wep-key0", "wep-key-type"] {
+                doc.remove("wifi-security", key);
+            }
+        }
+    }
+    
+    // Optional PMF is what marks WPA2/WPA3 transition mode
+    if *security == Security::Transition {
+        doc.set("wifi-security", "pmf", "2");
+    } else if doc.get("wifi-security", "pmf") == Some("2") {
+        doc.remove("wifi-security", "pmf");
+    }
+}
+
+/// Type 1 is a raw 40/104-bit WEP key (5/13 chars or 10/26 hex digits), 2 a passphrase
+fn wep_key_type(key: &str) -> &'static str {
+    match key.len() {
+        5 | 13 => "1",
+        10 | 26 if key.chars().all(|c| c.is_ascii_hexdigit()) => "1",
+        _ => "2",
+    }
+}
+
+/// Write the `[802-1x]` section, dropping keys the EAP method does not use
+fn write_enterprise(doc: &mut KeyFile, enterprise: &EnterpriseSettings, password: &str) {
+    let tls = enterprise.eap == "tls";
+    let keys = [
+        ("identity", enterprise.identity.as_str()),
+        ("anonymous-identity", enterprise.anonymous_identity.as_str()),
+        ("phase2-auth", if tls { "" } else { enterprise.phase2_auth.as_str() }),
+        ("password", if tls { "" } else { password }),
+        ("ca-cert", enterprise.ca_cert.as_str()),
+        ("client-cert", if tls { enterprise.client_cert.as_str() } else { "" }),
+        ("private-key", if tls { enterprise.private_key.as_str() } else { "" }),
+        ("private-key-password", if tls { enterprise.private_key_password.as_str() } else { "" }),
+        ("domain-suffix-match", enterprise.domain_suffix_match.as_str()),
+    ];
+    
+    if parse_enterprise(doc).eap != enterprise.eap {
+        doc.set("802-1x", "eap", &format!("{};", enterprise.eap));
+    }
+    for (key, value) in keys {
+        if value.is_empty() {
+            doc.remove("802-1x", key);
+        } else {
+            doc.set("802-1x", key, value);
+        }
+    }
+}
+
+/// Sanitized `<ssid>.nmconnection` filename, matching `sanitize_filename` in lib/utils.sh
+pub fn connection_filename(ssid: &str) -> String {
+    let safe_name: String = ssid.chars()
+        .map(|c| if "<>:\"/\\|?*".contains(c) { '_' } else { c })
+        .collect();
+    format!("{}.nmconnection", safe_name.trim_matches(|c| c == ' ' || c == '.'))
+}
+
+/// Load the editable settings of a connection file
+pub fn load_settings(path: &Path) -> Result<NetworkSettings, String> {
+    let doc = KeyFile::load(path)?;
+    let network = parse_file(path).ok_or("Connection file has no SSID")?;
+    
+    Ok(NetworkSettings {
+        ssid: network.ssid,
+        password: network.password.unwrap_or_default(),
+        security: network.security,
+        hidden: doc.get("wifi", "hidden") == Some("true"),
+        autoconnect: doc.get("connection", "autoconnect") != Some("false"),
+        priority: doc.get("connection", "autoconnect-priority")
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(0),
+        metered: Metered::from_keyfile(doc.get("connection", "metered")),
+        mac_address: MacAddress::from_keyfile(
+            doc.get("wifi", "cloned-mac-address"),
+            doc.get("wifi", "mac-address-randomization"),
+        ),
+        enterprise: network.enterprise.unwrap_or_default(),
+    })
+}
+
+/// Set the key of an existing profile for the security it already uses,
+/// leaving everything else as it is. Returns `false` without writing if that
+/// security has no key of its own (open, OWE, enterprise...).
+pub fn set_password(path: &Path, password: &str) -> Result<bool, String> {
+    let mut doc = KeyFile::load(path)?;
+    match Security::detect(|key| doc.get("wifi-security", key)) {
+        Security::Wep => {
+            doc.set("wifi-security", "wep-key0", password);
+            doc.set("wifi-security", "wep-key-type", wep_key_type(password));
+        }
+        Security::WpaPsk | Security::Transition | Security::Sae => {
+            doc.set("wifi-security", "psk", password);
+            doc.remove("wifi-security", "psk-flags");
+        }
+        _ => return Ok(false),
+    }
+    doc.save(path)?;
+    Ok(true)
+}
+
+/// Write back only the settings that changed, keeping the uuid and all other keys.
+/// Renaming the SSID also renames the file and drops the old report row so
+/// `sync-local` does not recreate the old profile. Returns the new file path.
+pub fn update_network(
+    config: &Config,
+    path: &Path,
+    original: &NetworkSettings,
+    updated: &NetworkSettings,
+) -> Result<PathBuf, String> {
+    let mut doc = KeyFile::load(path)?;
+    let connections_dir = path.parent().unwrap_or(Path::new("."));
+    
+    let new_path = if updated.ssid != original.ssid {
+        path.with_file_name(connection_filename(&updated.ssid))
+    } else {
+        path.to_path_buf()
+    };
+    if new_path != path && new_path.exists() {
+        return Err(format!("A connection file for '{}' already exists", updated.ssid));
+    }
+    
+    let mut enterprise = updated.enterprise.clone();
+    if updated.ssid != original.ssid {
+        doc.set("connection", "id", &updated.ssid);
+        doc.set("wifi", "ssid", &updated.ssid);
+        move_certificates(connections_dir, &original.ssid, &updated.ssid, &mut doc, &mut enterprise)?;
+    }
+    
+    if updated.security != original.security
+        || updated.password != original.password
+        || updated.enterprise != original.enterprise
+    {
+        let enterprise = if updated.security == Security::Enterprise {
+            import_certificates(connections_dir, &updated.ssid, &enterprise)?
+        } else {
+            EnterpriseSettings::default()
+        };
+        write_security(&mut doc, &updated.security, &updated.password, &enterprise);
+    }
+    
+    if updated.hidden != original.hidden {
+        doc.set("wifi", "hidden", if updated.hidden { "true" } else { "false" });
+    }
+    if updated.autoconnect != original.autoconnect {
+        doc.set("connection", "autoconnect", if updated.autoconnect { "true" } else { "false" });
+    }
+    if updated.priority != original.priority {
+        doc.set("connection", "autoconnect-priority", &updated.priority.to_string());
+    }
+    if updated.metered != original.metered {
+        match updated.metered.keyfile_value() {
+            Some(value) => doc.set("connection", "metered", value),
+            None => {
+                doc.remove("connection", "metered");
+            }
+        }
+    }
+    if updated.mac_address != original.mac_address {
+        // cloned-mac-address takes over from the deprecated key
+        doc.remove("wifi", "mac-address-randomization");
+        match updated.mac_address.keyfile_value() {
+            Some(value) => doc.set("wifi", "cloned-mac-address", value),
+            None => {
+                doc.remove("wifi", "cloned-mac-address");
+            }
+        }
+    }
+    
+    doc.save(&new_path)?;
+    
+    if new_path != path {
+        fs::remove_file(path)
+            .map_err(|e| format!("Failed to remove old file: {}", e))?;
+        report::remove_rows(&config.report_file, &[original.ssid.as_str()])?;
+    }
+    
+    Ok(new_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn rename_moves_the_certificates() {
+        let config = Config::for_test("rename");
+        let ca = config.base_dir.join("ca.pem");
+        fs::write(&ca, "certificate").unwrap();
+        let enterprise = EnterpriseSettings {
+            eap: "peap".to_string(),
+            identity: "user".to_string(),
+            phase2_auth: "mschapv2".to_string(),
+            ca_cert: ca.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        create_network(&config, "Office", "secret", &Security::Enterprise, &enterprise).unwrap();
+        
+        let path = config.local_dir.join(connection_filename("Office"));
+        let original = load_settings(&path).unwrap();
+        let updated = NetworkSettings { ssid: "Office 2".to_string(), ..original.clone() };
+        let new_path = update_network(&config, &path, &original, &updated).unwrap();
+        
+        let ca_cert = load_settings(&new_path).unwrap().enterprise.ca_cert;
+        let new_dir = cert_dir(&config.local_dir, "Office 2").canonicalize().unwrap();
+        assert!(Path::new(&ca_cert).starts_with(&new_dir), "{}", ca_cert);
+        assert_eq!(fs::read_to_string(&ca_cert).unwrap(), "certificate");
+        assert!(!cert_dir(&config.local_dir, "Office").exists());
+        assert!(!path.exists());
+        
+        fs::remove_dir_all(&config.base_dir).unwrap();
+    }
+    
+    #[test]
+    fn imported_certificates_survive_an_edit_through_a_symlink() {
+        let real = Config::for_test("symlink-real").base_dir;
+        let link = real.with_file_name(format!("wifi-manager-symlink-link-{}", std::process::id()));
+        let _ = fs::remove_file(&link);
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+        let config = Config::at(link.clone());
+        
+        let ca = real.join("ca.pem");
+        fs::write(&ca, "certificate").unwrap();
+        let enterprise = EnterpriseSettings {
+            eap: "peap".to_string(),
+            identity: "user".to_string(),
+            phase2_auth: "mschapv2".to_string(),
+            ca_cert: ca.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        create_network(&config, "Office", "secret", &Security::Enterprise, &enterprise).unwrap();
+        
+        let path = config.local_dir.join(connection_filename("Office"));
+        let original = load_settings(&path).unwrap();
+        let updated = NetworkSettings { password: "new secret".to_string(), ..original.clone() };
+        update_network(&config, &path, &original, &updated).unwrap();
+        
+        let ca_cert = load_settings(&path).unwrap().enterprise.ca_cert;
+        assert_eq!(ca_cert, original.enterprise.ca_cert);
+        assert_eq!(fs::read_to_string(&ca_cert).unwrap(), "certificate");
+        
+        fs::remove_file(&link).unwrap();
+        fs::remove_dir_all(&real).unwrap();
+    }
+    
+    #[test]
+    fn set_password_keeps_the_security() {
+        let dir = Config::for_test("set-password").base_dir;
+        let path = dir.join("Lab.nmconnection");
+        let content = "[wifi]\nssid=Lab\n\n[wifi-security]\nkey-mgmt=sae\npsk-flags=1\n";
+        fs::write(&path, content).unwrap();
+        
+        assert!(set_password(&path, "secret").unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[wifi]\nssid=Lab\n\n[wifi-security]\nkey-mgmt=sae\npsk=secret\n");
+        
+        let open = dir.join("Open.nmconnection");
+        fs::write(&open, "[wifi]\nssid=Open\n").unwrap();
+        assert!(!set_password(&open, "secret").unwrap());
+        assert_eq!(fs::read_to_string(&open).unwrap(), "[wifi]\nssid=Open\n");
+        
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn unknown_security_is_left_alone() {
+        let content = "[wifi-security]\nkey-mgmt=wapi-psk\npsk=secret\n\n[802-1x]\neap=peap;\n";
+        let mut doc = KeyFile::parse(content);
+        let security = Security::detect(|key| doc.get("wifi-security", key));
+        assert_eq!(security, Security::Unknown("wapi-psk".to_string()));
+        
+        write_security(&mut doc, &security, "", &EnterpriseSettings::default());
+        assert_eq!(doc.to_string(), content);
+    }
 }
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use crate::backend::config::Config;
+use crate::backend::diff::{self, FileDiff};
+use crate::backend::helper;
+use crate::backend::helper_protocol::Request;
+use crate::backend::script::Action;
+
+/// Which way files are copied
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Direction {
+    /// Local files to `SYSTEM_DIR` (`install`)
+    #[default]
+    Install,
+    /// `SYSTEM_DIR` to local files (`backup`)
+    Backup,
+}
+
+impl Direction {
+    /// Name of the matching wifi-manager.sh command
+    pub fn command(self) -> &'static str {
+        match self {
+            Direction::Install => "install",
+            Direction::Backup => "backup",
+        }
+    }
+}
+
+/// What applying a plan item does, named like the `--json` actions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanAction {
+    /// Copy a local file that is not installed
+    Install,
+    /// Overwrite the other side's copy
+    Update,
+    /// Copy a system file that has no local copy
+    Pull,
+}
+
+impl fmt::Display for PlanAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PlanAction::Install => "install",
+            PlanAction::Update => "update",
+            PlanAction::Pull => "pull",
+        })
+    }
+}
+
+impl PlanAction {
+    /// How the result reports the item once it was applied
+    pub fn done(self) -> Action {
+        match self {
+            PlanAction::Install => Action::Installed,
+            PlanAction::Update => Action::Updated,
+            PlanAction::Pull => Action::Pulled,
+        }
+    }
+}
+
+/// A file install or backup would copy
+#[derive(Debug, Clone)]
+pub struct PlanItem {
+    pub filename: String,
+    pub action: PlanAction,
+    pub diff: FileDiff,
+}
+
+/// The files `cmd_install` or `cmd_backup` would copy, decided the same way:
+/// missing on the other side, or different according to `get_file_hash`
+pub async fn plan(config: &Config, direction: Direction) -> Result<Vec<PlanItem>, String> {
+    let local = read_local_files(config)?;
+    let system = diff::read_all_system_files(config).await?;
+
+    let (sources, targets) = match direction {
+        Direction::Install => (&local, &system),
+        Direction::Backup => (&system, &local),
+    };
+
+    let mut filenames: Vec<&String> = sources.keys().collect();
+    filenames.sort();
+
+    Ok(filenames
+        .into_iter()
+        .filter_map(|filename| {
+            let action = match (targets.contains_key(filename), direction) {
+                (false, Direction::Install) => PlanAction::Install,
+                (false, Direction::Backup) => PlanAction::Pull,
+                (true, _) => PlanAction::Update,
+            };
+            let diff = FileDiff::new(
+                filename,
+                local.get(filename).map(String::as_str),
+                system.get(filename).map(String::as_str),
+            );
+            (action != PlanAction::Update || diff.hash_differs).then(|| PlanItem {
+                filename: filename.clone(),
+                action,
+                diff,
+            })
+        })
+        .collect())
+}
+
+/// Copy the given items. Install writes them all and reloads NetworkManager
+/// in one helper batch, which is undone as a whole if anything fails; backup
+/// reads the system files (through the helper if they are root-only) and
+/// writes the local copies as the user.
+pub async fn apply(config: &Config, direction: Direction, items: &[PlanItem]) -> Result<Vec<(String, Action)>, String> {
+    let filenames: Vec<String> = items.iter().map(|item| item.filename.clone()).collect();
+
+    match direction {
+        Direction::Install => {
+            let local = read_local_files(config)?;
+            let mut requests = Vec::new();
+            for file in &filenames {
+                let content = local
+                    .get(file)
+                    .ok_or_else(|| format!("{} is no longer in {}", file, config.local_dir.display()))?;
+                requests.push(Request::Write { file: file.clone(), content: content.clone() });
+            }
+            requests.push(Request::Reload);
+            helper::run(config, requests).await?;
+        }
+        Direction::Backup => {
+            let system = diff::read_system_files(config, &filenames).await?;
+            fs::create_dir_all(&config.local_dir)
+                .map_err(|e| format!("Failed to create directory: {}", e))?;
+            for file in &filenames {
+                let content = system
+                    .get(file)
+                    .ok_or_else(|| format!("{} is no longer installed", file))?;
+                fs::write(config.local_dir.join(file), content)
+                    .map_err(|e| format!("Failed to write {}: {}", file, e))?;
+            }
+        }
+    }
+
+    Ok(items.iter().map(|item| (item.filename.clone(), item.action.done())).collect())
+}
+
+/// Connection files of the local directory, by filename; none if it does not exist yet
+fn read_local_files(config: &Config) -> Result<HashMap<String, String>, String> {
+    let entries = match fs::read_dir(&config.local_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(HashMap::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", config.local_dir.display(), e)),
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "nmconnection"))
+        .map(|path| {
+            let filename = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            fs::read_to_string(&path)
+                .map(|content| (filename, content))
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+        })
+        .collect()
+}
@@ -0,0 +1,182 @@
+use std::fmt;
+use std::path::Path;
+use std::process::ExitStatus;
+use tokio::process::Command;
+use crate::backend::askpass;
+
+/// A tool that runs the helper as root
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// polkit, with the `io.github.finjener.wifi-manager.helper` action; needs an authentication agent
+    Pkexec,
+    /// `sudo -A`, with the GUI as the askpass program
+    Sudo,
+    /// systemd's run0, which also authenticates through polkit
+    Run0,
+    /// `doas -n`: only works without a password prompt (`nopass` or `persist`)
+    Doas,
+}
+
+impl Method {
+    /// In the order `auto` tries them
+    pub const ALL: [Method; 4] = [Method::Pkexec, Method::Sudo, Method::Run0, Method::Doas];
+
+    /// Program name, also the `PRIVILEGE_METHOD` value
+    pub fn name(self) -> &'static str {
+        match self {
+            Method::Pkexec => "pkexec",
+            Method::Sudo => "sudo",
+            Method::Run0 => "run0",
+            Method::Doas => "doas",
+        }
+    }
+
+    /// Parse a `PRIVILEGE_METHOD` value; `None` for `auto` and unknown values
+    pub fn from_name(name: &str) -> Option<Method> {
+        Method::ALL.into_iter().find(|m| m.name() == name.trim())
+    }
+
+    pub fn is_installed(self) -> bool {
+        find_program(self.name())
+    }
+
+    /// `program` run as root through this method
+    pub fn command(self, program: &Path) -> Command {
+        let mut command = Command::new(self.name());
+        match self {
+            Method::Pkexec | Method::Run0 => {}
+            Method::Sudo => {
+                command
+                    .args(["-A", "--"])
+                    .env("SUDO_ASKPASS", askpass::shim_path())
+                    .env(askpass::SOCKET_VAR, askpass::socket_path());
+            }
+            Method::Doas => {
+                command.args(["-n", "--"]);
+            }
+        }
+        command.arg(program);
+        command
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Method::Sudo => "sudo -A",
+            method => method.name(),
+        })
+    }
+}
+
+/// Why the helper could not be started as root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrivilegeError {
+    /// `auto` found none of the tools
+    NoneInstalled,
+    /// The configured tool is not installed
+    NotInstalled(Method),
+    /// pkexec or run0 found no polkit authentication agent to ask for the password
+    AgentUnavailable(Method),
+    /// doas wants a password, and can only ask for it in a terminal
+    NeedsTerminal(Method),
+    /// The password dialog was cancelled
+    Dismissed,
+    /// Wrong password, or the user may not run the helper as root
+    NotAuthorized,
+    /// Anything else, with the tool's last error line
+    Failed(String),
+}
+
+impl fmt::Display for PrivilegeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrivilegeError::NoneInstalled => {
+                write!(f, "No privilege escalation tool found; install pkexec, sudo, run0 or doas")
+            }
+            PrivilegeError::NotInstalled(method) => {
+                write!(f, "{} is not installed; choose another privilege method in Settings", method)
+            }
+            PrivilegeError::AgentUnavailable(method) => write!(
+                f,
+                "{} needs a polkit authentication agent and none is running; start one or choose sudo in Settings",
+                method
+            ),
+            PrivilegeError::NeedsTerminal(method) => write!(
+                f,
+                "{} can only ask for a password in a terminal; allow the helper with nopass or persist, or choose another method in Settings",
+                method
+            ),
+            PrivilegeError::Dismissed => write!(f, "Authentication dismissed"),
+            PrivilegeError::NotAuthorized => write!(f, "Not authorized to change the system connections"),
+            PrivilegeError::Failed(line) => write!(f, "Privileged helper failed: {}", line),
+        }
+    }
+}
+
+/// Tools found in PATH, in the order `auto` tries them
+pub fn available() -> Vec<Method> {
+    Method::ALL.into_iter().filter(|m| m.is_installed()).collect()
+}
+
+/// `PRIVILEGE_METHOD` values the settings offer: `auto` and the installed tools
+pub fn choices() -> Vec<String> {
+    std::iter::once("auto")
+        .chain(available().into_iter().map(Method::name))
+        .map(String::from)
+        .collect()
+}
+
+/// The configured method, or the first installed one for `auto`
+pub fn resolve(configured: Option<Method>) -> Result<Method, PrivilegeError> {
+    match configured {
+        Some(method) => Ok(method),
+        None => available().into_iter().next().ok_or(PrivilegeError::NoneInstalled),
+    }
+}
+
+/// Tell apart why `method` failed before the helper printed a result, from its
+/// exit status and error output
+pub fn classify(method: Method, status: ExitStatus, stderr: &str) -> PrivilegeError {
+    let has = |needle: &str| stderr.contains(needle);
+
+    match method {
+        // pkexec exits 126 when the dialog was dismissed, 127 when not authorised
+        Method::Pkexec if status.code() == Some(126) => return PrivilegeError::Dismissed,
+        Method::Pkexec if has("No authentication agent") => return PrivilegeError::AgentUnavailable(method),
+        Method::Pkexec if has("Not authorized") => return PrivilegeError::NotAuthorized,
+        Method::Sudo if has("no password was provided") => return PrivilegeError::Dismissed,
+        Method::Sudo if has("incorrect password") || has("not in the sudoers") || has("not allowed to") => {
+            return PrivilegeError::NotAuthorized
+        }
+        Method::Run0 if has("Interactive authentication required") => {
+            return PrivilegeError::AgentUnavailable(method)
+        }
+        Method::Run0 if has("Access denied") => return PrivilegeError::NotAuthorized,
+        Method::Doas if has("tty") || has("Authorization required") => {
+            return PrivilegeError::NeedsTerminal(method)
+        }
+        Method::Doas if has("Authentication failed") || has("not permitted") => {
+            return PrivilegeError::NotAuthorized
+        }
+        _ => {}
+    }
+
+    match stderr.lines().rfind(|l| !l.trim().is_empty()) {
+        Some(line) => PrivilegeError::Failed(line.trim().to_string()),
+        None => PrivilegeError::Failed(status.to_string()),
+    }
+}
+
+/// Whether `program` is an executable file in PATH
+fn find_program(program: &str) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    let Some(path) = std::env::var_os("PATH") else {
+        return false;
+    };
+    std::env::split_paths(&path).any(|dir| {
+        std::fs::metadata(dir.join(program))
+            .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+    })
+}
@@ -0,0 +1,110 @@
+use std::fs;
+use std::path::Path;
+
+/// Split a markdown table row into trimmed cells, honouring `\|` escapes.
+/// Returns `None` for non-table lines, the header and the separator row.
+pub fn split_row(line: &str) -> Option<Vec<String>> {
+    let line = line.trim();
+    if !line.starts_with('|') || line.starts_with("| Network Name") || line.starts_with("|--") {
+        return None;
+    }
+
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut chars = line[1..].chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                // Keep escapes intact; unescape_cell decodes them
+                cell.push(c);
+                if let Some(next) = chars.next() {
+                    cell.push(next);
+                }
+            }
+            '|' => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            _ => cell.push(c),
+        }
+    }
+
+    Some(cells)
+}
+
+/// Escape `\` and `|` for a table cell, as `escape_report_cell` in lib/utils.sh does
+pub fn escape_cell(cell: &str) -> String {
+    cell.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+/// Decode `\|` and `\\` escapes used for SSIDs and passwords in the report
+pub fn unescape_cell(cell: &str) -> String {
+    let mut result = String::new();
+    let mut chars = cell.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some(next @ ('|' | '\\')) => result.push(next),
+                Some(next) => {
+                    result.push(c);
+                    result.push(next);
+                }
+                None => result.push(c),
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Remove the rows of the given SSIDs from the report, leaving everything else untouched
+pub fn remove_rows(report_file: &Path, ssids: &[&str]) -> Result<(), String> {
+    if !report_file.exists() {
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(report_file)
+        .map_err(|e| format!("Failed to read report: {}", e))?;
+
+    let kept: Vec<&str> = content
+        .split_inclusive('\n')
+        .filter(|line| {
+            split_row(line)
+                .and_then(|cells| cells.first().map(|c| unescape_cell(c)))
+                .is_none_or(|ssid| !ssids.contains(&ssid.as_str()))
+        })
+        .collect();
+
+    fs::write(report_file, kept.concat())
+        .map_err(|e| format!("Failed to write report: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escaped_pipes_stay_in_their_cell() {
+        let line = format!("| {} | WPA/WPA2 | `{}` | Saved |", escape_cell("A|B\\C"), escape_cell("p|a\\ss|"));
+        assert_eq!(line, r"| A\|B\\C | WPA/WPA2 | `p\|a\\ss\|` | Saved |");
+
+        let cells = split_row(&line).unwrap();
+        assert_eq!(cells.len(), 4);
+        assert_eq!(unescape_cell(&cells[0]), "A|B\\C");
+        assert_eq!(cells[1], "WPA/WPA2");
+        assert_eq!(unescape_cell(&cells[2]), "`p|a\\ss|`");
+        assert_eq!(cells[3], "Saved");
+    }
+
+    #[test]
+    fn header_and_other_lines_are_not_rows() {
+        assert_eq!(split_row("| Network Name | Security Type | Password | Status |"), None);
+        assert_eq!(split_row("|--------------|---------------|----------|--------|"), None);
+        assert_eq!(split_row("# Saved WiFi Networks"), None);
+    }
+
+    #[test]
+    fn unknown_escapes_are_kept() {
+        assert_eq!(unescape_cell(r"a\nb\"), r"a\nb\");
+    }
+}
@@ -1,15 +1,182 @@
-use std::path::PathBuf;
-use std::process::Stdio;
-use tokio::process::Command;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+use iced::futures::channel::mpsc;
+use iced::futures::future::{self, Either};
+use iced::futures::{stream, SinkExt, Stream, StreamExt};
+use serde::Deserialize;
+use tokio::process::{Child, Command};
+use tokio::time::Instant;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
+
+/// Which pipe a line of output came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Progress of a running wifi-manager.sh command
+#[derive(Debug, Clone)]
+pub enum ScriptEvent {
+    /// One line of output that is not an event, ANSI colours stripped
+    Line(OutputStream, String),
+    /// One `--json` event
+    Event(JsonEvent),
+    /// The script is waiting for an answer, see `ScriptHandle::answer`
+    Prompt(Prompt),
+    /// The process exited or could not be started; always the last event
+    Finished(Result<ScriptOutput, String>),
+}
+
+/// Severity of a `--json` event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        })
+    }
+}
+
+/// What a command did to a network (`log_network` in lib/utils.sh)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Created,
+    Updated,
+    Skipped,
+    Installed,
+    Pulled,
+    Trashed,
+    Forgotten,
+    Restored,
+    #[serde(other)]
+    Other,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Action::Created => "created",
+            Action::Updated => "updated",
+            Action::Skipped => "skipped",
+            Action::Installed => "installed",
+            Action::Pulled => "pulled",
+            Action::Trashed => "trashed",
+            Action::Forgotten => "forgotten",
+            Action::Restored => "restored",
+            Action::Other => "changed",
+        })
+    }
+}
+
+/// One line of `wifi-manager.sh --json` output
+#[derive(Debug, Clone, Deserialize)]
+pub struct JsonEvent {
+    pub level: Level,
+    /// The command that emitted the event, e.g. `install`
+    #[serde(default)]
+    pub operation: String,
+    #[serde(default)]
+    pub dry_run: bool,
+    pub message: String,
+    pub action: Option<Action>,
+    pub ssid: Option<String>,
+    /// Connection filename, e.g. `Home.nmconnection`
+    pub file: Option<String>,
+    /// Final counters of the command, e.g. `installed` and `updated`
+    pub counts: Option<BTreeMap<String, u64>>,
+}
+
+/// Decode a `--json` event line; anything else (adb/nmcli output...) gives `None`
+pub fn parse_event(line: &str) -> Option<JsonEvent> {
+    if !line.starts_with('{') {
+        return None;
+    }
+    serde_json::from_str(line).ok()
+}
+
+/// How a command ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptStatus {
+    /// The process exited on its own
+    Exited,
+    /// Stopped from the GUI
+    Cancelled,
+    /// Stopped after running longer than its timeout
+    TimedOut,
+}
 
 #[derive(Debug, Clone)]
 pub struct ScriptOutput {
-    pub stdout: String,
-    pub stderr: String,
     pub success: bool,
+    /// Exit code, `None` if the process was killed by a signal
+    pub code: Option<i32>,
+    pub status: ScriptStatus,
 }
 
+/// A yes/no question asked by `confirm` in lib/utils.sh
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    pub question: String,
+    /// What the question is about, e.g. the file on the device that will be replaced
+    pub detail: String,
+}
+
+/// Start of a prompt line: `@@WIFI_MANAGER_PROMPT@@<TAB>confirm<TAB><question><TAB><detail>`
+const PROMPT_MARKER: &str = "@@WIFI_MANAGER_PROMPT@@";
+
+fn parse_prompt(line: &str) -> Option<Prompt> {
+    let mut fields = line.strip_prefix(PROMPT_MARKER)?.strip_prefix('\t')?.splitn(3, '\t');
+    if fields.next()? != "confirm" {
+        return None;
+    }
+
+    Some(Prompt {
+        question: fields.next().unwrap_or_default().to_string(),
+        detail: fields.next().unwrap_or_default().to_string(),
+    })
+}
+
+#[derive(Debug)]
+enum Control {
+    Cancel,
+    Answer(bool),
+}
+
+/// Controls a command started with `stream_command`
+#[derive(Debug)]
+pub struct ScriptHandle(mpsc::UnboundedSender<Control>);
+
+impl ScriptHandle {
+    pub fn cancel(&self) {
+        let _ = self.0.unbounded_send(Control::Cancel);
+    }
+
+    /// Answer the script's pending `Prompt`
+    pub fn answer(&self, yes: bool) {
+        let _ = self.0.unbounded_send(Control::Answer(yes));
+    }
+}
+
+/// Time between SIGTERM and SIGKILL when stopping a command
+const KILL_GRACE: Duration = Duration::from_secs(5);
+
 /// Get the path to wifi-manager.sh
 /// 
 /// Resolution order:
@@ -26,7 +193,7 @@ pub fn get_script_path() -> PathBuf {
         }
         eprintln!("[WARN] WIFI_MANAGER_SCRIPT set but path not found: {}", env_path);
     }
-    
+
     // 2. Relative to executable (for installed/release builds)
     if let Ok(exe_path) = std::env::current_exe() {
         // From gui/target/debug or gui/target/release -> project root
@@ -39,7 +206,7 @@ pub fn get_script_path() -> PathBuf {
         if path.exists() {
             return path;
         }
-        
+
         // Also try: target/debug -> project root (if gui folder is missing)
         let mut path = exe_path;
         path.pop(); // remove binary
@@ -50,7 +217,7 @@ pub fn get_script_path() -> PathBuf {
             return path;
         }
     }
-    
+
     // 3. Check current working directory
     if let Ok(cwd) = std::env::current_dir() {
         let mut path = cwd.clone();
@@ -58,7 +225,7 @@ pub fn get_script_path() -> PathBuf {
         if path.exists() {
             return path;
         }
-        
+
         // 4. Check parent of current working directory
         path = cwd;
         path.pop();
@@ -67,7 +234,7 @@ pub fn get_script_path() -> PathBuf {
             return path;
         }
     }
-    
+
     // Last resort - return relative path and let caller handle missing file
     PathBuf::from("wifi-manager.sh")
 }
@@ -76,7 +243,7 @@ pub fn get_script_path() -> PathBuf {
 fn strip_ansi_codes(s: &str) -> String {
     let mut result = String::new();
     let mut chars = s.chars().peekable();
-    
+
     while let Some(c) = chars.next() {
         if c == '\x1b' {
             // Skip until 'm' (end of ANSI sequence)
@@ -93,124 +260,175 @@ fn strip_ansi_codes(s: &str) -> String {
     result
 }
 
-/// Run a wifi-manager.sh command and capture output using direct process execution
-pub async fn run_command_in_terminal(cmd: &str) -> Result<ScriptOutput, String> {
+/// Build the process for a wifi-manager.sh command with extra arguments (e.g. SSIDs)
+fn build_command(cmd: &str, args: &[String], base_dir: &Path) -> Result<Command, String> {
     let script_path = get_script_path();
-    
+
     if !script_path.exists() {
         return Err(format!("Script not found at: {:?}\nSet WIFI_MANAGER_SCRIPT environment variable to override.", script_path));
     }
-    
+
     let script_dir = script_path.parent().unwrap_or(&PathBuf::from(".")).to_path_buf();
-    
-    let mut child = Command::new("bash")
+
+    // Questions come back as prompt markers instead of blocking on a terminal read
+    let mut command = Command::new("bash");
+    command
         .arg(&script_path)
         .arg(cmd)
+        .arg("--json")
+        .args(args)
         .current_dir(&script_dir)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to spawn process: {}", e))?;
-    
-    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
-    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
-    
-    // Read stdout and stderr concurrently
-    let stdout_reader = BufReader::new(stdout);
-    let stderr_reader = BufReader::new(stderr);
-    
-    let mut stdout_lines = stdout_reader.lines();
-    let mut stderr_lines = stderr_reader.lines();
-    
-    let mut stdout_output = String::new();
-    let mut stderr_output = String::new();
-    
-    // Collect stdout
-    while let Ok(Some(line)) = stdout_lines.next_line().await {
-        stdout_output.push_str(&line);
-        stdout_output.push('\n');
-    }
-    
-    // Collect stderr
-    while let Ok(Some(line)) = stderr_lines.next_line().await {
-        stderr_output.push_str(&line);
-        stderr_output.push('\n');
-    }
-    
-    let status = child.wait().await
-        .map_err(|e| format!("Failed to wait for process: {}", e))?;
-    
-    Ok(ScriptOutput {
-        stdout: strip_ansi_codes(&stdout_output),
-        stderr: strip_ansi_codes(&stderr_output),
-        success: status.success(),
-    })
+        .env("WIFI_MANAGER_PROMPTS", "marker")
+        .env("WIFI_MANAGER_BASE", base_dir);
+    Ok(command)
 }
 
-/// Run command with sudo using pkexec and capture output directly
-pub async fn run_sudo_command_in_terminal(cmd: &str) -> Result<ScriptOutput, String> {
-    let script_path = get_script_path();
-    
-    if !script_path.exists() {
-        return Err(format!("Script not found at: {:?}\nSet WIFI_MANAGER_SCRIPT environment variable to override.", script_path));
-    }
-    
-    let script_dir = script_path.parent().unwrap_or(&PathBuf::from(".")).to_path_buf();
-    let script_path_str = script_path.to_string_lossy().to_string();
-    let script_dir_str = script_dir.to_string_lossy().to_string();
-    
-    // pkexec doesn't preserve working directory, so use sh -c
-    let shell_cmd = format!("cd '{}' && bash '{}' {}", script_dir_str, script_path_str, cmd);
-    
-    let mut child = Command::new("pkexec")
-        .arg("sh")
-        .arg("-c")
-        .arg(&shell_cmd)
+/// Run a wifi-manager.sh command, emitting each line of output as soon as it is
+/// written. Ends with `ScriptEvent::Finished`; the handle answers prompts and
+/// stops the command early. `base_dir` is passed as `WIFI_MANAGER_BASE` so the
+/// script works on the GUI's active workspace. `timeout` does not run while a
+/// prompt waits for an answer.
+pub fn stream_command(
+    cmd: &str,
+    args: &[String],
+    base_dir: &Path,
+    timeout: Option<Duration>,
+) -> (impl Stream<Item = ScriptEvent>, ScriptHandle) {
+    let command = build_command(cmd, args, base_dir);
+    let (control_tx, control_rx) = mpsc::unbounded();
+
+    let events = iced::stream::channel(100, move |mut output| async move {
+        let result = match command {
+            Ok(command) => run(command, timeout, control_rx, &mut output).await,
+            Err(e) => Err(e),
+        };
+        let _ = output.send(ScriptEvent::Finished(result)).await;
+    });
+
+    (events, ScriptHandle(control_tx))
+}
+
+enum Input {
+    Line(OutputStream, String),
+    /// Both pipes are closed
+    Eof,
+    Control(Control),
+}
+
+async fn run(
+    mut command: Command,
+    timeout: Option<Duration>,
+    control: mpsc::UnboundedReceiver<Control>,
+    output: &mut mpsc::Sender<ScriptEvent>,
+) -> Result<ScriptOutput, String> {
+    let program = command.as_std().get_program().to_string_lossy().to_string();
+    let mut child = command
+        // Own process group, so stopping the command also reaches the script's children
+        .process_group(0)
+        .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()
-        .map_err(|e| format!("Failed to spawn pkexec: {}", e))?;
-    
+        .map_err(|e| format!("Failed to spawn {}: {}", program, e))?;
+
+
+    let mut stdin = child.stdin.take();
     let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
     let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
-    
-    let stdout_reader = BufReader::new(stdout);
-    let stderr_reader = BufReader::new(stderr);
-    
-    let mut stdout_lines = stdout_reader.lines();
-    let mut stderr_lines = stderr_reader.lines();
-    
-    let mut stdout_output = String::new();
-    let mut stderr_output = String::new();
-    
-    while let Ok(Some(line)) = stdout_lines.next_line().await {
-        stdout_output.push_str(&line);
-        stdout_output.push('\n');
-    }
-    
-    while let Ok(Some(line)) = stderr_lines.next_line().await {
-        stderr_output.push_str(&line);
-        stderr_output.push('\n');
+
+    // Read both pipes at once so a chatty stream cannot fill up and block the child
+    let lines = stream::select(
+        read_lines(stdout, OutputStream::Stdout),
+        read_lines(stderr, OutputStream::Stderr),
+    )
+    .map(|(source, line)| Input::Line(source, line))
+    .chain(stream::once(future::ready(Input::Eof)));
+    let mut inputs = pin!(stream::select(lines, control.map(Input::Control)));
+
+    let mut deadline = timeout.map(|limit| Instant::now() + limit);
+    // Time left on the deadline while a prompt waits for an answer
+    let mut paused: Option<Duration> = None;
+
+    let status = loop {
+        let running = deadline.filter(|_| paused.is_none());
+        let expired = pin!(async move {
+            match running {
+                Some(at) => tokio::time::sleep_until(at).await,
+                None => future::pending().await,
+            }
+        });
+        match future::select(inputs.next(), expired).await {
+            Either::Left((Some(Input::Line(source, line)), _)) => {
+                let line = strip_ansi_codes(&line);
+                let event = match (parse_prompt(&line), parse_event(&line)) {
+                    (Some(prompt), _) if source == OutputStream::Stdout => {
+                        if let (Some(at), None) = (deadline, paused) {
+                            paused = Some(at.saturating_duration_since(Instant::now()));
+                        }
+                        ScriptEvent::Prompt(prompt)
+                    }
+                    (_, Some(event)) => ScriptEvent::Event(event),
+                    _ => ScriptEvent::Line(source, line),
+                };
+                let _ = output.send(event).await;
+            }
+            Either::Left((Some(Input::Control(Control::Answer(yes))), _)) => {
+                if let Some(left) = paused.take() {
+                    deadline = Some(Instant::now() + left);
+                }
+                if let Some(stdin) = stdin.as_mut() {
+                    let _ = stdin.write_all(if yes { b"y\n" } else { b"n\n" }).await;
+                }
+            }
+            Either::Left((Some(Input::Control(Control::Cancel)), _)) => break ScriptStatus::Cancelled,
+            Either::Left((Some(Input::Eof) | None, _)) => break ScriptStatus::Exited,
+            Either::Right(_) => break ScriptStatus::TimedOut,
+        }
+    };
+    // EOF for a script still waiting on an answer
+    drop(stdin);
+
+    if status != ScriptStatus::Exited {
+        let exit = terminate(&mut child).await;
+        return Ok(ScriptOutput {
+            success: false,
+            code: exit.and_then(|e| e.code()),
+            status,
+        });
     }
-    
-    let status = child.wait().await
-        .map_err(|e| format!("Failed to wait for pkexec: {}", e))?;
-    
+
+    let exit = child.wait().await
+        .map_err(|e| format!("Failed to wait for {}: {}", program, e))?;
+
     Ok(ScriptOutput {
-        stdout: strip_ansi_codes(&stdout_output),
-        stderr: strip_ansi_codes(&stderr_output),
-        success: status.success(),
+        success: exit.success(),
+        code: exit.code(),
+        status,
     })
 }
 
-/// Run a wifi-manager.sh command and return output (silent mode - no terminal)
-pub async fn run_command(cmd: &str) -> Result<ScriptOutput, String> {
-    // Delegate to the main implementation
-    run_command_in_terminal(cmd).await
+/// SIGTERM the child's process group, then SIGKILL it if it is still running after
+/// `KILL_GRACE`. Returns `None` if it outlived both, and we stop waiting for it.
+pub async fn terminate(child: &mut Child) -> Option<ExitStatus> {
+    let group = -(child.id()? as libc::pid_t);
+
+    for signal in [libc::SIGTERM, libc::SIGKILL] {
+        // SAFETY: kill() has no memory-safety preconditions
+        unsafe { libc::kill(group, signal) };
+        if let Ok(exit) = tokio::time::timeout(KILL_GRACE, child.wait()).await {
+            return exit.ok();
+        }
+    }
+    None
 }
 
-/// Run command with sudo (for install/backup) - silent mode
-pub async fn run_sudo_command(cmd: &str) -> Result<ScriptOutput, String> {
-    // Delegate to the main implementation
-    run_sudo_command_in_terminal(cmd).await
+/// Lines of a pipe, tagged with their source
+fn read_lines<R>(reader: R, source: OutputStream) -> impl Stream<Item = (OutputStream, String)>
+where
+    R: AsyncRead + Unpin,
+{
+    stream::unfold(BufReader::new(reader).lines(), move |mut lines| async move {
+        let line = lines.next_line().await.ok().flatten()?;
+        Some(((source, line), lines))
+    })
 }
@@ -0,0 +1,383 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use crate::backend::config::Config;
+use crate::backend::localtime;
+use crate::backend::nmconnection;
+use crate::backend::report;
+use crate::models::network::{EnterpriseSettings, Network, Security};
+
+/// A network row of the report table
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportRow {
+    pub ssid: String,
+    pub security: String,
+    /// Empty when the report says `N/A`
+    pub password: String,
+}
+
+/// What a sync changed, by SSID
+#[derive(Debug, Clone, Default)]
+pub struct SyncChanges {
+    /// Connection files created from report rows
+    pub created: Vec<String>,
+    /// Existing files that were missing the password the report has
+    pub passwords_injected: Vec<String>,
+    /// Enterprise rows without a file; the report has no 802.1X settings to recreate them
+    pub skipped_enterprise: Vec<String>,
+    /// Report rows added, removed, or whose security or password changed
+    pub report_added: Vec<String>,
+    pub report_removed: Vec<String>,
+    pub report_changed: Vec<String>,
+}
+
+impl SyncChanges {
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty()
+            && self.passwords_injected.is_empty()
+            && self.skipped_enterprise.is_empty()
+            && self.report_added.is_empty()
+            && self.report_removed.is_empty()
+            && self.report_changed.is_empty()
+    }
+}
+
+/// A field that differs between a report row and its connection file
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldDiff {
+    Security { report: String, file: String },
+    /// Password values are left out so the details never show them
+    Password { in_report: bool, in_file: bool },
+}
+
+impl fmt::Display for FieldDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldDiff::Security { report, file } => write!(f, "security: {} in report, {} in file", report, file),
+            FieldDiff::Password { in_report: true, in_file: false } => f.write_str("password missing in file"),
+            FieldDiff::Password { in_report: false, in_file: true } => f.write_str("password missing in report"),
+            FieldDiff::Password { .. } => f.write_str("password differs"),
+        }
+    }
+}
+
+/// A network in both the report and the files whose fields differ
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    pub ssid: String,
+    pub fields: Vec<FieldDiff>,
+}
+
+/// Per-network comparison of the report with the connection files
+#[derive(Debug, Clone, Default)]
+pub struct SyncStatus {
+    pub only_in_report: Vec<String>,
+    pub only_in_files: Vec<String>,
+    pub mismatched: Vec<Mismatch>,
+    /// Number of networks in the connection files
+    pub network_count: usize,
+}
+
+impl SyncStatus {
+    pub fn is_synced(&self) -> bool {
+        self.only_in_report.is_empty() && self.only_in_files.is_empty() && self.mismatched.is_empty()
+    }
+
+    /// One-line description for the status bar
+    pub fn summary(&self) -> String {
+        if self.is_synced() {
+            return format!("✓ Synced ({} networks)", self.network_count);
+        }
+
+        let parts: Vec<String> = [
+            (self.only_in_report.len(), "only in report"),
+            (self.only_in_files.len(), "only in files"),
+            (self.mismatched.len(), "mismatched"),
+        ]
+        .iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, label)| format!("{} {}", count, label))
+        .collect();
+        format!("⚠ Not synced ({} networks): {}", self.network_count, parts.join(", "))
+    }
+}
+
+/// Compare SSID, security and password of every report row with the connection files
+pub fn status(config: &Config) -> SyncStatus {
+    // An unreadable report is treated like a missing one
+    let report_rows = parse_report(&config.report_file).unwrap_or_default();
+    let file_rows: Vec<ReportRow> = nmconnection::load_networks(&config.local_dir)
+        .iter()
+        .map(row_for)
+        .collect();
+
+    let report: BTreeMap<&str, &ReportRow> = report_rows.iter().map(|r| (r.ssid.as_str(), r)).collect();
+    let files: BTreeMap<&str, &ReportRow> = file_rows.iter().map(|r| (r.ssid.as_str(), r)).collect();
+
+    let mut status = SyncStatus {
+        network_count: file_rows.len(),
+        ..Default::default()
+    };
+
+    for (ssid, row) in &report {
+        let Some(file) = files.get(ssid) else {
+            status.only_in_report.push(ssid.to_string());
+            continue;
+        };
+
+        let mut fields = Vec::new();
+        if row.security != file.security {
+            fields.push(FieldDiff::Security { report: row.security.clone(), file: file.security.clone() });
+        }
+        if row.password != file.password {
+            fields.push(FieldDiff::Password {
+                in_report: !row.password.is_empty(),
+                in_file: !file.password.is_empty(),
+            });
+        }
+        if !fields.is_empty() {
+            status.mismatched.push(Mismatch { ssid: ssid.to_string(), fields });
+        }
+    }
+    status.only_in_files = files
+        .keys()
+        .filter(|ssid| !report.contains_key(*ssid))
+        .map(|ssid| ssid.to_string())
+        .collect();
+
+    status
+}
+
+/// Bidirectional sync, same as `sync_report` in lib/sync.sh: create missing
+/// connection files from the report, then regenerate the report from the files
+pub fn sync(config: &Config) -> Result<SyncChanges, String> {
+    fs::create_dir_all(&config.local_dir)
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    let old_rows = parse_report(&config.report_file)?;
+    let mut changes = sync_report_to_connections(config, &old_rows)?;
+
+    let networks = nmconnection::load_networks(&config.local_dir);
+    let new_rows: Vec<ReportRow> = networks.iter().map(row_for).collect();
+    write_report(&config.report_file, &new_rows)?;
+
+    let old: BTreeMap<&str, &ReportRow> = old_rows.iter().map(|r| (r.ssid.as_str(), r)).collect();
+    let new: BTreeMap<&str, &ReportRow> = new_rows.iter().map(|r| (r.ssid.as_str(), r)).collect();
+    for (ssid, row) in &new {
+        match old.get(ssid) {
+            None => changes.report_added.push(ssid.to_string()),
+            Some(old_row) if old_row != row => changes.report_changed.push(ssid.to_string()),
+            Some(_) => {}
+        }
+    }
+    changes.report_removed = old
+        .keys()
+        .filter(|ssid| !new.contains_key(*ssid))
+        .map(|ssid| ssid.to_string())
+        .collect();
+
+    Ok(changes)
+}
+
+/// Read the network rows of the report; a missing report has none
+pub fn parse_report(report_file: &Path) -> Result<Vec<ReportRow>, String> {
+    if !report_file.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(report_file)
+        .map_err(|e| format!("Failed to read report: {}", e))?;
+
+    Ok(content
+        .lines()
+        .filter_map(report::split_row)
+        .filter_map(|cells| {
+            let ssid = report::unescape_cell(cells.first()?);
+            if ssid.is_empty() {
+                return None;
+            }
+            let security = cells.get(1).cloned().unwrap_or_default();
+            let password = cells.get(2).map(|p| {
+                let p = p.strip_prefix('`').unwrap_or(p);
+                p.strip_suffix('`').unwrap_or(p)
+            });
+            let password = match password {
+                Some("N/A") | None => String::new(),
+                Some(p) => report::unescape_cell(p),
+            };
+            Some(ReportRow { ssid, security, password })
+        })
+        .collect())
+}
+
+/// Create connection files for report rows that have none, and inject passwords
+/// the report knows into files that lack one
+fn sync_report_to_connections(config: &Config, rows: &[ReportRow]) -> Result<SyncChanges, String> {
+    let mut changes = SyncChanges::default();
+
+    for row in rows {
+        let filepath = config.local_dir.join(nmconnection::connection_filename(&row.ssid));
+        let security = Security::from_label(&row.security, !row.password.is_empty());
+
+        if security == Security::Enterprise {
+            if !filepath.exists() {
+                changes.skipped_enterprise.push(row.ssid.clone());
+            }
+        } else if !filepath.exists() {
+            nmconnection::create_network(config, &row.ssid, &row.password, &security, &EnterpriseSettings::default())?;
+            changes.created.push(row.ssid.clone());
+        } else {
+            let has_password = nmconnection::parse_file(&filepath)
+                .and_then(|n| n.password)
+                .is_some_and(|p| !p.is_empty());
+            // Keep the file's own security, e.g. SAE in a file the report lists as WPA2
+            if !has_password && !row.password.is_empty() && nmconnection::set_password(&filepath, &row.password)? {
+                changes.passwords_injected.push(row.ssid.clone());
+            }
+        }
+    }
+
+    Ok(changes)
+}
+
+fn row_for(network: &Network) -> ReportRow {
+    ReportRow {
+        ssid: network.ssid.clone(),
+        security: network.security.report_label(),
+        password: network.password.clone().unwrap_or_default(),
+    }
+}
+
+/// Write the report in the format of `generate_report`
+fn write_report(report_file: &Path, rows: &[ReportRow]) -> Result<(), String> {
+    let active = active_connections();
+
+    let mut content = format!(
+        "# Saved WiFi Networks\n\n*Generated on {}*\n\n## Network List\n\n\
+         | Network Name | Security Type | Password | Status |\n\
+         |--------------|---------------|----------|--------|\n",
+        localtime::now()
+    );
+
+    for row in rows {
+        let ssid = report::escape_cell(&row.ssid);
+        let password = if row.password.is_empty() {
+            "N/A".to_string()
+        } else {
+            format!("`{}`", report::escape_cell(&row.password))
+        };
+        let status = if active.contains(&row.ssid) { "Active" } else { "Saved" };
+        content.push_str(&format!("| {} | {} | {} | {} |\n", ssid, row.security, password, status));
+    }
+
+    content.push_str("\n---\n\n**⚠️ Security Notice**: This file contains sensitive network passwords.\n");
+
+    fs::write(report_file, content)
+        .map_err(|e| format!("Failed to write report: {}", e))
+}
+
+/// Names of the active NetworkManager connections; empty without nmcli
+fn active_connections() -> Vec<String> {
+    Command::new("nmcli")
+        .args(["-t", "-f", "NAME", "connection", "show", "--active"])
+        .output()
+        .map(|out| String::from_utf8_lossy(&out.stdout).lines().map(String::from).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_report_rows(config: &Config, rows: &[&str]) {
+        let content = format!(
+            "# Saved WiFi Networks\n\n## Network List\n\n\
+             | Network Name | Security Type | Password | Status |\n\
+             |--------------|---------------|----------|--------|\n\
+             {}\n\n---\n",
+            rows.join("\n")
+        );
+        fs::write(&config.report_file, content).unwrap();
+    }
+
+    fn row(ssid: &str, security: &str, password: &str) -> ReportRow {
+        ReportRow { ssid: ssid.to_string(), security: security.to_string(), password: password.to_string() }
+    }
+
+    #[test]
+    fn parse_report_reads_escaped_and_open_rows() {
+        let config = Config::for_test("sync-parse");
+        write_report_rows(&config, &[
+            r"| A\|B\\C | WPA/WPA2 | `p\|w` | Saved |",
+            "| Open Cafe | Open | N/A | Saved |",
+            "| Hidden Lab | WPA3 | `h1dden` | Active |",
+        ]);
+
+        assert_eq!(parse_report(&config.report_file).unwrap(), [
+            row("A|B\\C", "WPA/WPA2", "p|w"),
+            row("Open Cafe", "Open", ""),
+            row("Hidden Lab", "WPA3", "h1dden"),
+        ]);
+        assert_eq!(parse_report(&config.base_dir.join("missing.md")).unwrap(), []);
+
+        fs::remove_dir_all(&config.base_dir).unwrap();
+    }
+
+    #[test]
+    fn status_reports_new_matching_and_mismatching_networks() {
+        let config = Config::for_test("sync-status");
+        let none = EnterpriseSettings::default();
+        nmconnection::create_network(&config, "Match", "same", &Security::WpaPsk, &none).unwrap();
+        nmconnection::create_network(&config, "Mismatch", "file-pw", &Security::WpaPsk, &none).unwrap();
+        nmconnection::create_network(&config, "OnlyFile", "pw", &Security::WpaPsk, &none).unwrap();
+        write_report_rows(&config, &[
+            "| Match | WPA/WPA2 | `same` | Saved |",
+            "| Mismatch | WPA3 | `report-pw` | Saved |",
+            "| OnlyReport | Open | N/A | Saved |",
+        ]);
+
+        let status = status(&config);
+        assert_eq!(status.network_count, 3);
+        assert_eq!(status.only_in_report, ["OnlyReport"]);
+        assert_eq!(status.only_in_files, ["OnlyFile"]);
+        assert_eq!(status.mismatched.len(), 1);
+        assert_eq!(status.mismatched[0].ssid, "Mismatch");
+        assert_eq!(status.mismatched[0].fields, [
+            FieldDiff::Security { report: "WPA3".to_string(), file: "WPA/WPA2".to_string() },
+            FieldDiff::Password { in_report: true, in_file: true },
+        ]);
+        assert!(!status.is_synced());
+
+        fs::remove_dir_all(&config.base_dir).unwrap();
+    }
+
+    #[test]
+    fn sync_creates_files_and_injects_passwords() {
+        let config = Config::for_test("sync-sync");
+        fs::create_dir_all(&config.local_dir).unwrap();
+        let lab = config.local_dir.join("Lab.nmconnection");
+        fs::write(&lab, "[connection]\nid=Lab\n\n[wifi]\nssid=Lab\n\n[wifi-security]\nkey-mgmt=sae\n").unwrap();
+        write_report_rows(&config, &[
+            "| New | WPA/WPA2 | `new-pw` | Saved |",
+            "| Lab | WPA/WPA2 | `lab-pw` | Saved |",
+            "| Corp | Enterprise | N/A | Saved |",
+        ]);
+
+        let changes = sync(&config).unwrap();
+        assert_eq!(changes.created, ["New"]);
+        assert_eq!(changes.passwords_injected, ["Lab"]);
+        assert_eq!(changes.skipped_enterprise, ["Corp"]);
+        assert_eq!(changes.report_removed, ["Corp"]);
+        assert_eq!(changes.report_changed, ["Lab"]);
+
+        let new = nmconnection::parse_file(&config.local_dir.join("New.nmconnection")).unwrap();
+        assert_eq!(new.password.as_deref(), Some("new-pw"));
+        let lab = nmconnection::parse_file(&lab).unwrap();
+        assert_eq!(lab.security, Security::Sae);
+        assert_eq!(lab.password.as_deref(), Some("lab-pw"));
+        assert!(status(&config).is_synced());
+
+        fs::remove_dir_all(&config.base_dir).unwrap();
+    }
+}
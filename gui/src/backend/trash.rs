@@ -0,0 +1,169 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::backend::config::Config;
+use crate::backend::helper;
+use crate::backend::helper_protocol::Request;
+use crate::backend::keyfile::KeyFile;
+use crate::backend::localtime;
+use crate::backend::report;
+use crate::models::network::Network;
+
+/// A deleted connection kept in `data/.trash/<id>.nmconnection` with `<id>.meta`
+/// (same layout as `cmd_delete` in lib/trash.sh)
+#[derive(Debug, Clone)]
+pub struct TrashEntry {
+    pub id: String,
+    pub ssid: String,
+    pub filename: String,
+    pub deleted: u64,
+    pub forgotten: bool,
+}
+
+impl TrashEntry {
+    /// Deletion time in local time, formatted like the report's timestamp
+    pub fn deleted_display(&self) -> String {
+        localtime::format(self.deleted as i64)
+    }
+}
+
+/// Move a network's connection file into the trash and drop its report row.
+/// The entry is marked forgotten by `forget` once that succeeds.
+pub fn move_to_trash(config: &Config, network: &Network) -> Result<TrashEntry, String> {
+    let path = network.path.as_ref().ok_or("Network has no connection file")?;
+    let filename = path
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .ok_or("Invalid connection file path")?;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let deleted = now.as_secs();
+    // Nanoseconds keep two deletes of the same name within a second apart
+    let id = format!("{}.{:09}-{}", deleted, now.subsec_nanos(), filename.trim_end_matches(".nmconnection"));
+
+    let dir = &config.trash_dir;
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create trash directory: {}", e))?;
+
+    fs::rename(path, dir.join(format!("{}.nmconnection", id)))
+        .map_err(|e| format!("Failed to move {} to trash: {}", filename, e))?;
+
+    let mut meta = KeyFile::default();
+    meta.set("trash", "ssid", &network.ssid);
+    meta.set("trash", "filename", &filename);
+    meta.set("trash", "deleted", &deleted.to_string());
+    meta.set("trash", "forgotten", "false");
+    meta.save(&dir.join(format!("{}.meta", id)))?;
+
+    report::remove_rows(&config.report_file, &[network.ssid.as_str()])?;
+
+    Ok(TrashEntry { id, ssid: network.ssid.clone(), filename, deleted, forgotten: false })
+}
+
+/// Remove the trashed networks' files from the system directory and reload
+/// NetworkManager, in one helper batch. Returns the SSIDs that were installed.
+pub async fn forget(config: &Config, entries: &[TrashEntry]) -> Result<Vec<String>, String> {
+    let mut requests: Vec<Request> = entries
+        .iter()
+        .map(|entry| Request::Delete { file: entry.filename.clone() })
+        .collect();
+    requests.push(Request::Reload);
+
+    let responses = helper::run(config, requests).await?;
+    for entry in entries {
+        // The system files are gone either way; the flag only labels the trash view
+        let _ = mark_forgotten(config, entry);
+    }
+    Ok(entries
+        .iter()
+        .zip(responses)
+        .filter(|(_, response)| response.existed == Some(true))
+        .map(|(entry, _)| entry.ssid.clone())
+        .collect())
+}
+
+fn mark_forgotten(config: &Config, entry: &TrashEntry) -> Result<(), String> {
+    let path = config.trash_dir.join(format!("{}.meta", entry.id));
+    let mut meta = KeyFile::load(&path)?;
+    meta.set("trash", "forgotten", "true");
+    meta.save(&path)
+}
+
+/// List trashed connections, newest first
+pub fn list(config: &Config) -> Vec<TrashEntry> {
+    let mut entries = Vec::new();
+
+    if let Ok(dir) = fs::read_dir(&config.trash_dir) {
+        for entry in dir.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|e| e != "nmconnection") {
+                continue;
+            }
+            let id = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+            let meta = KeyFile::load(&path.with_extension("meta")).unwrap_or_default();
+            let fallback_name = id.split_once('-').map_or(id.as_str(), |(_, name)| name).to_string();
+
+            entries.push(TrashEntry {
+                ssid: meta.get("trash", "ssid").unwrap_or(&fallback_name).to_string(),
+                filename: meta
+                    .get("trash", "filename")
+                    .map(|f| f.to_string())
+                    .unwrap_or_else(|| format!("{}.nmconnection", fallback_name)),
+                deleted: meta.get("trash", "deleted").and_then(|d| d.parse().ok()).unwrap_or(0),
+                forgotten: meta.get("trash", "forgotten") == Some("true"),
+                id,
+            });
+        }
+    }
+
+    entries.sort_by_key(|e| std::cmp::Reverse(e.deleted));
+    entries
+}
+
+/// Move a trashed connection back into the connections directory
+pub fn restore(config: &Config, entry: &TrashEntry) -> Result<PathBuf, String> {
+    let dir = &config.trash_dir;
+    let connections_dir = &config.local_dir;
+    let target = connections_dir.join(&entry.filename);
+
+    if target.exists() {
+        return Err(format!("{} already exists, not restoring", entry.filename));
+    }
+    fs::create_dir_all(connections_dir)
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    fs::rename(dir.join(format!("{}.nmconnection", entry.id)), &target)
+        .map_err(|e| format!("Failed to restore {}: {}", entry.filename, e))?;
+    let _ = fs::remove_file(dir.join(format!("{}.meta", entry.id)));
+
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::nmconnection;
+    use crate::models::network::{EnterpriseSettings, Security};
+
+    #[test]
+    fn deletes_of_the_same_name_do_not_collide() {
+        let config = Config::for_test("trash-ids");
+        let mut ids = Vec::new();
+        for password in ["first", "second"] {
+            nmconnection::create_network(&config, "Home", password, &Security::WpaPsk, &EnterpriseSettings::default()).unwrap();
+            let network = nmconnection::load_networks(&config.local_dir).remove(0);
+            ids.push(move_to_trash(&config, &network).unwrap().id);
+        }
+        assert_ne!(ids[0], ids[1]);
+
+        let entries = list(&config);
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|e| e.ssid == "Home" && e.filename == "Home.nmconnection" && !e.forgotten));
+
+        mark_forgotten(&config, &entries[0]).unwrap();
+        let forgotten: Vec<bool> = list(&config).iter().map(|e| e.forgotten).collect();
+        assert_eq!(forgotten.iter().filter(|f| **f).count(), 1);
+
+        fs::remove_dir_all(&config.base_dir).unwrap();
+    }
+}
@@ -0,0 +1,149 @@
+use std::fs;
+use std::path::PathBuf;
+use crate::backend::config::{self, Config};
+use crate::backend::keyfile::KeyFile;
+
+/// Name of the workspace that uses the data directory from config.env
+pub const DEFAULT: &str = "Default";
+
+const SECTION_PREFIX: &str = "workspace ";
+
+/// A named data directory with its own connections, report and XML file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Workspace {
+    pub name: String,
+    pub base_dir: PathBuf,
+}
+
+/// The known workspaces and the active one, stored in
+/// `~/.config/wifi-manager/workspaces`:
+///
+/// ```text
+/// [general]
+/// active=Lab
+///
+/// [workspace Lab]
+/// base=/home/me/wifi-lab
+/// ```
+#[derive(Debug, Clone)]
+pub struct Workspaces {
+    /// `DEFAULT` first, then the user's workspaces in file order
+    pub list: Vec<Workspace>,
+    pub active: String,
+}
+
+impl Workspaces {
+    /// Load the workspace list; a missing or unknown active workspace falls back to `DEFAULT`
+    pub fn load() -> Self {
+        let doc = workspaces_path()
+            .and_then(|path| KeyFile::load(&path).ok())
+            .unwrap_or_default();
+
+        let mut list = vec![Workspace {
+            name: DEFAULT.to_string(),
+            base_dir: Config::load().base_dir,
+        }];
+        for section in doc.sections() {
+            if let (Some(name), Some(base)) = (section.strip_prefix(SECTION_PREFIX), doc.get(section, "base")) {
+                if name != DEFAULT && !list.iter().any(|w| w.name == name) {
+                    list.push(Workspace { name: name.to_string(), base_dir: PathBuf::from(base) });
+                }
+            }
+        }
+
+        let active = doc
+            .get("general", "active")
+            .filter(|name| list.iter().any(|w| w.name == *name))
+            .unwrap_or(DEFAULT)
+            .to_string();
+
+        Workspaces { list, active }
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.list.iter().map(|w| w.name.clone()).collect()
+    }
+
+    /// Configuration of the active workspace
+    pub fn config(&self) -> Config {
+        match self.list.iter().find(|w| w.name == self.active) {
+            Some(workspace) if workspace.name != DEFAULT => Config::with_base(&workspace.base_dir),
+            _ => Config::load(),
+        }
+    }
+
+    /// Make `name` the active workspace and remember it for the next start
+    pub fn select(&mut self, name: &str) -> Result<(), String> {
+        if !self.list.iter().any(|w| w.name == name) {
+            return Err(format!("Unknown workspace: {}", name));
+        }
+        self.active = name.to_string();
+        self.save()
+    }
+
+    /// Add a workspace for an existing directory and make it active
+    pub fn add(&mut self, name: &str, base_dir: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Workspace name is required".to_string());
+        }
+        if self.list.iter().any(|w| w.name == name) {
+            return Err(format!("A workspace named '{}' already exists", name));
+        }
+
+        let base_dir = PathBuf::from(base_dir.trim());
+        if !base_dir.is_absolute() {
+            return Err("Workspace directory must be an absolute path".to_string());
+        }
+        config::check_writable(&base_dir)?;
+
+        self.list.push(Workspace { name: name.to_string(), base_dir });
+        self.active = name.to_string();
+        self.save()
+    }
+
+    /// Forget the active workspace (its files are kept) and go back to `DEFAULT`
+    pub fn remove_active(&mut self) -> Result<(), String> {
+        if self.active == DEFAULT {
+            return Err("The default workspace cannot be removed".to_string());
+        }
+        self.list.retain(|w| w.name != self.active);
+        self.active = DEFAULT.to_string();
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let path = workspaces_path().ok_or("HOME is not set")?;
+
+        // Rewrite in place so comments and unknown keys survive
+        let mut doc = KeyFile::load(&path).unwrap_or_default();
+        let stale: Vec<String> = doc
+            .sections()
+            .filter(|s| s.starts_with(SECTION_PREFIX))
+            .map(String::from)
+            .collect();
+        for section in stale {
+            let name = &section[SECTION_PREFIX.len()..];
+            if !self.list.iter().any(|w| w.name == name) {
+                doc.remove_section(&section);
+            }
+        }
+
+        doc.set("general", "active", &self.active);
+        for workspace in self.list.iter().filter(|w| w.name != DEFAULT) {
+            let section = format!("{}{}", SECTION_PREFIX, workspace.name);
+            doc.set(&section, "base", &workspace.base_dir.to_string_lossy());
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+        doc.save(&path)
+    }
+}
+
+/// `~/.config/wifi-manager/workspaces`, next to the user config.env
+fn workspaces_path() -> Option<PathBuf> {
+    config::user_config_path().and_then(|p| p.parent().map(|dir| dir.join("workspaces")))
+}
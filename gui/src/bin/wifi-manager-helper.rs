@@ -0,0 +1,265 @@
+//! Privileged helper of the WiFi Manager GUI, started as root through pkexec
+//! (with the `io.github.finjener.wifi-manager.helper` polkit action), sudo,
+//! run0 or doas.
+//!
+//! Reads one JSON `Batch` on stdin, runs it as root and prints a `BatchResult`
+//! on stdout. It can only list, read, write and delete connection files directly
+//! inside the system connections directory, and reload NetworkManager.
+
+#[path = "../backend/helper_protocol.rs"]
+mod helper_protocol;
+
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Read, Write};
+use std::os::unix::fs::{MetadataExt, OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+use helper_protocol::{Batch, BatchResult, Request, Response, SYSTEM_DIR};
+
+/// Set by pkexec, sudo (and run0) and doas; the directory override is ignored when present
+const ELEVATION_VARS: &[&str] = &["PKEXEC_UID", "SUDO_UID", "DOAS_USER"];
+
+/// A file as it was before the batch changed it
+struct Snapshot {
+    file: String,
+    /// `None` if the batch created the file
+    previous: Option<Previous>,
+}
+
+struct Previous {
+    content: Vec<u8>,
+    mode: u32,
+    uid: u32,
+    gid: u32,
+}
+
+fn main() -> ExitCode {
+    let mut input = String::new();
+    let result = match std::io::stdin().read_to_string(&mut input) {
+        Ok(_) => match serde_json::from_str::<Batch>(&input) {
+            Ok(batch) => run(&system_dir(), batch.requests),
+            Err(e) => failed(format!("Invalid request: {}", e)),
+        },
+        Err(e) => failed(format!("Failed to read request: {}", e)),
+    };
+
+    let ok = result.error.is_none();
+    match serde_json::to_string(&result) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("Failed to encode result: {}", e),
+    }
+    if ok { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
+
+fn failed(error: String) -> BatchResult {
+    BatchResult { error: Some(error), ..Default::default() }
+}
+
+/// Only a direct run as root (e.g. for testing) may use `WIFI_MANAGER_SYSTEM_DIR`
+fn system_dir() -> PathBuf {
+    let elevated = ELEVATION_VARS.iter().any(|var| std::env::var_os(var).is_some());
+    match std::env::var_os("WIFI_MANAGER_SYSTEM_DIR") {
+        Some(dir) if !elevated => PathBuf::from(dir),
+        _ => PathBuf::from(SYSTEM_DIR),
+    }
+}
+
+fn run(dir: &Path, requests: Vec<Request>) -> BatchResult {
+    let mut snapshots: Vec<Snapshot> = Vec::new();
+    let mut responses = Vec::new();
+    let mut reloaded = false;
+
+    for request in requests {
+        if matches!(request, Request::Reload) {
+            reloaded = true;
+        }
+        match handle(dir, request, &mut snapshots) {
+            Ok(response) => responses.push(response),
+            Err(error) => {
+                responses.push(Response { ok: false, error: Some(error.clone()), ..Default::default() });
+                let error = match rollback(dir, &snapshots, reloaded) {
+                    Ok(()) => error,
+                    Err(e) => format!("{}; {}", error, e),
+                };
+                let rolled_back = !snapshots.is_empty();
+                return BatchResult { responses, rolled_back, error: Some(error) };
+            }
+        }
+    }
+
+    BatchResult { responses, ..Default::default() }
+}
+
+fn handle(dir: &Path, request: Request, snapshots: &mut Vec<Snapshot>) -> Result<Response, String> {
+    let ok = Response { ok: true, ..Default::default() };
+
+    match request {
+        Request::List => {
+            let mut files: Vec<String> = fs::read_dir(dir)
+                .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+                .flatten()
+                .filter(|entry| entry.path().is_file())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| check_filename(name).is_ok())
+                .collect();
+            files.sort();
+            Ok(Response { files: Some(files), ..ok })
+        }
+        Request::Read { file } => {
+            let path = connection_path(dir, &file)?;
+            match fs::read_to_string(&path) {
+                Ok(content) => Ok(Response { content: Some(content), ..ok }),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(ok),
+                Err(e) => Err(format!("Failed to read {}: {}", file, e)),
+            }
+        }
+        Request::Write { file, content } => {
+            let path = connection_path(dir, &file)?;
+            let existed = snapshot(&path, &file, snapshots)?;
+            write_atomic(&path, content.as_bytes(), 0o600, 0, 0)
+                .map_err(|e| format!("Failed to write {}: {}", file, e))?;
+            Ok(Response { existed: Some(existed), ..ok })
+        }
+        Request::Delete { file } => {
+            let path = connection_path(dir, &file)?;
+            let existed = snapshot(&path, &file, snapshots)?;
+            if existed {
+                fs::remove_file(&path).map_err(|e| format!("Failed to delete {}: {}", file, e))?;
+            }
+            Ok(Response { existed: Some(existed), ..ok })
+        }
+        Request::Reload => {
+            reload()?;
+            let written: Vec<&str> = snapshots
+                .iter()
+                .map(|s| s.file.as_str())
+                .filter(|file| dir.join(file).exists())
+                .collect();
+            let missing = unloaded(dir, &written)?;
+            if !missing.is_empty() {
+                return Err(format!("NetworkManager did not load: {}", missing.join(", ")));
+            }
+            Ok(ok)
+        }
+    }
+}
+
+/// `dir/file`, for a plain connection filename only
+fn connection_path(dir: &Path, file: &str) -> Result<PathBuf, String> {
+    check_filename(file)?;
+    Ok(dir.join(file))
+}
+
+fn check_filename(file: &str) -> Result<(), String> {
+    let valid = file.ends_with(".nmconnection")
+        && file.len() > ".nmconnection".len()
+        && !file.starts_with('.')
+        && !file.contains(['/', '\0']);
+    if valid { Ok(()) } else { Err(format!("Not a connection filename: {}", file)) }
+}
+
+/// Remember the file before its first change in this batch; returns whether it exists
+fn snapshot(path: &Path, file: &str, snapshots: &mut Vec<Snapshot>) -> Result<bool, String> {
+    let previous = match fs::read(path) {
+        Ok(content) => {
+            let meta = fs::metadata(path).map_err(|e| format!("Failed to read {}: {}", file, e))?;
+            Some(Previous { content, mode: meta.mode() & 0o7777, uid: meta.uid(), gid: meta.gid() })
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => None,
+        Err(e) => return Err(format!("Failed to read {}: {}", file, e)),
+    };
+    let existed = previous.is_some();
+
+    if !snapshots.iter().any(|s| s.file == file) {
+        snapshots.push(Snapshot { file: file.to_string(), previous });
+    }
+    Ok(existed)
+}
+
+/// Put every changed file back as it was, newest change first
+fn rollback(dir: &Path, snapshots: &[Snapshot], reloaded: bool) -> Result<(), String> {
+    let mut failed = Vec::new();
+
+    for snapshot in snapshots.iter().rev() {
+        let path = dir.join(&snapshot.file);
+        let restored = match &snapshot.previous {
+            Some(p) => write_atomic(&path, &p.content, p.mode, p.uid, p.gid),
+            None => match fs::remove_file(&path) {
+                Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+        };
+        if restored.is_err() {
+            failed.push(snapshot.file.as_str());
+        }
+    }
+
+    if reloaded {
+        reload()?;
+    }
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("rollback failed for {}", failed.join(", ")))
+    }
+}
+
+/// Write next to `path` and rename over it, so NetworkManager never sees a partial
+/// file (it ignores names starting with a dot)
+fn write_atomic(path: &Path, content: &[u8], mode: u32, uid: u32, gid: u32) -> std::io::Result<()> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = path.with_file_name(format!(".{}.tmp", name));
+
+    let result = (|| {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(mode)
+            .open(&tmp)?;
+        file.write_all(content)?;
+        std::os::unix::fs::fchown(&file, Some(uid), Some(gid))?;
+        // The mode given to open() is reduced by the umask
+        file.set_permissions(fs::Permissions::from_mode(mode))?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+fn reload() -> Result<(), String> {
+    let status = Command::new("nmcli")
+        .args(["connection", "reload"])
+        .status()
+        .map_err(|e| format!("Failed to run nmcli: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err("NetworkManager failed to reload its connections".to_string())
+    }
+}
+
+/// Which of `files` NetworkManager has no connection for
+fn unloaded<'a>(dir: &Path, files: &[&'a str]) -> Result<Vec<&'a str>, String> {
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+    let output = Command::new("nmcli")
+        .args(["-g", "FILENAME", "connection", "show"])
+        .output()
+        .map_err(|e| format!("Failed to run nmcli: {}", e))?;
+    let loaded = String::from_utf8_lossy(&output.stdout);
+
+    Ok(files
+        .iter()
+        .copied()
+        .filter(|file| {
+            let path = dir.join(file);
+            !loaded.lines().any(|line| Path::new(line) == path)
+        })
+        .collect())
+}
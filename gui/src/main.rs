@@ -2,17 +2,30 @@ mod backend;
 mod models;
 mod ui;
 
-use iced::widget::{column, container, row, text};
-use iced::{Element, Length, Task};
+use iced::widget::{button, column, container, horizontal_space, row, text};
+use iced::{Element, Length, Subscription, Task};
 use iced::clipboard;
-use std::path::PathBuf;
+use iced::task;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
-use backend::{nmconnection, script};
+use backend::{android_export, android_import, android_xml, askpass, diff, helper, nmconnection, plan, script, sync, trash};
+use backend::config::{self, Config};
+use backend::privilege::Method;
+use backend::workspace::Workspaces;
 use models::network::Network;
-use ui::{network_list, output_panel, toolbar, add_network_dialog};
+use backend::script::{Action, JsonEvent, Level, OutputStream, ScriptEvent, ScriptStatus};
+use ui::{network_list, output_panel, toolbar, add_network_dialog, edit_network_dialog, enterprise_form, import_preview, export_preview, delete_dialog, diff_view, plan_view, password_dialog, prompt_dialog, settings_view, sync_status, trash_view, workspace_bar};
 
 pub fn main() -> iced::Result {
+    // sudo -A runs this binary again to ask for the password
+    if let Some(socket) = std::env::var_os(askpass::SOCKET_VAR) {
+        let prompt = std::env::args().nth(1).unwrap_or_default();
+        std::process::exit(askpass::run_shim(Path::new(&socket), &prompt));
+    }
+
     iced::application("WiFi Manager", WifiManager::update, WifiManager::view)
+        .subscription(WifiManager::subscription)
         .theme(|_| iced::Theme::Dark)
         .window_size(iced::Size::new(1100.0, 650.0))
         .run_with(WifiManager::new)
@@ -28,96 +41,321 @@ pub enum Message {
     NetworkList(network_list::NetworkListMessage),
     // Add network dialog
     AddNetwork(add_network_dialog::AddNetworkMessage),
+    // Edit network dialog
+    EditNetwork(edit_network_dialog::EditNetworkMessage),
+    // Delete confirmation and trash view
+    Delete(delete_dialog::DeleteMessage),
+    Trash(trash_view::TrashMessage),
+    // Local vs system diff
+    Diff(diff_view::DiffMessage),
+    // Install/backup plan
+    Plan(plan_view::PlanMessage),
+    // Android import preview
+    ImportPreview(import_preview::ImportPreviewMessage),
+    // Merged WifiConfigStore.xml before push-android
+    ExportPreview(export_preview::ExportPreviewMessage),
+    // Answer to a question asked by the running script
+    Prompt(prompt_dialog::PromptMessage),
+    // sudo password prompt
+    Askpass(askpass::AskpassRequest),
+    Password(password_dialog::PasswordMessage),
     // Async results
-    CommandComplete(String),
+    Script(ScriptEvent),
     NetworksLoaded(Vec<Network>),
-    SyncStatusChecked(bool, usize),
+    SyncStatusChecked(sync::SyncStatus),
+    SyncStatus(sync_status::SyncStatusMessage),
     NetworkCreated(Result<(), String>),
+    Synced(Result<sync::SyncChanges, String>),
+    NetworkUpdated(Result<PathBuf, String>),
+    /// One result per network, in the order they were selected
+    NetworksDeleted(Vec<Result<trash::TrashEntry, String>>, bool),
+    NetworksForgotten(Result<Vec<String>, String>),
+    TrashLoaded(Vec<trash::TrashEntry>),
+    NetworkRestored(Result<PathBuf, String>),
+    DiffLoaded(Result<diff::FileDiff, String>),
+    PlanLoaded(plan::Direction, Result<Vec<plan::PlanItem>, String>),
+    PlanApplied(Result<Vec<(String, Action)>, String>),
+    ImportPreviewLoaded(Result<Vec<android_xml::AndroidNetwork>, String>),
+    ExportPreviewLoaded(Result<android_export::MergeResult, String>),
+    AndroidImported(Result<android_import::ImportResult, String>),
+    // Settings
+    Settings(settings_view::SettingsMessage),
+    SettingsLoaded(HashMap<String, String>),
+    SettingsSaved(Result<(), String>),
+    // Workspace switcher
+    Workspace(workspace_bar::WorkspaceMessage),
     // Refresh
     Refresh,
 }
 
 struct WifiManager {
     networks: Vec<Network>,
-    terminal_output: String,
-    is_synced: bool,
-    network_count: usize,
+    terminal_output: Vec<output_panel::OutputLine>,
+    sync_status: sync::SyncStatus,
+    show_sync_details: bool,
     is_loading: bool,
-    data_dir: PathBuf,
+    config: Config,
+    // Running script command, and how the last one ended if it did not succeed
+    running_command: Option<String>,
+    script_handle: Option<script::ScriptHandle>,
+    // Running privileged helper batch; aborting it stops the helper
+    helper_task: Option<task::Handle>,
+    prompt: Option<script::Prompt>,
+    password: Option<password_dialog::PasswordState>,
+    last_result: Option<String>,
+    // Counters and per-network actions reported by the last command's --json events
+    last_counts: Option<String>,
+    row_actions: HashMap<String, Action>,
     // New state
     search_query: String,
     show_passwords: Vec<bool>,
+    selected: Vec<bool>,
     add_network_state: add_network_dialog::AddNetworkState,
+    edit_network_state: edit_network_dialog::EditNetworkState,
+    import_preview_state: import_preview::ImportPreviewState,
+    export_preview_state: export_preview::ExportPreviewState,
+    delete_state: delete_dialog::DeleteState,
+    trash_entries: Vec<trash::TrashEntry>,
+    show_trash: bool,
+    file_diff: Option<diff::FileDiff>,
+    plan_state: plan_view::PlanState,
+    settings_state: settings_view::SettingsState,
+    workspaces: Workspaces,
+    workspace_form: workspace_bar::WorkspaceForm,
 }
 
 impl WifiManager {
     fn new() -> (Self, Task<Message>) {
-        let data_dir = get_data_dir();
+        let workspaces = Workspaces::load();
+        let config = workspaces.config();
+        let mut terminal_output = vec![
+            output_panel::OutputLine {
+                stream: OutputStream::Stdout,
+                level: None,
+                text: format!("[INFO] Workspace {}: {}", workspaces.active, config.base_dir.display()),
+            },
+            output_panel::OutputLine {
+                stream: OutputStream::Stdout,
+                level: None,
+                text: format!("[INFO] System connections: {}", config.system_dir.display()),
+            },
+        ];
+        if let Err(e) = helper::check_system_dir(&config) {
+            terminal_output.push(output_panel::OutputLine {
+                stream: OutputStream::Stderr,
+                level: None,
+                text: format!("[WARN] {}", e),
+            });
+        }
         
         (
             WifiManager {
                 networks: Vec::new(),
-                terminal_output: String::new(),
-                is_synced: false,
-                network_count: 0,
+                terminal_output,
+                sync_status: sync::SyncStatus::default(),
+                show_sync_details: false,
                 is_loading: true,
-                data_dir: data_dir.clone(),
+                config: config.clone(),
+                running_command: None,
+                script_handle: None,
+                helper_task: None,
+                prompt: None,
+                password: None,
+                last_result: None,
+                last_counts: None,
+                row_actions: HashMap::new(),
                 search_query: String::new(),
                 show_passwords: Vec::new(),
+                selected: Vec::new(),
                 add_network_state: add_network_dialog::AddNetworkState::new(),
+                edit_network_state: edit_network_dialog::EditNetworkState::default(),
+                import_preview_state: import_preview::ImportPreviewState::default(),
+                export_preview_state: export_preview::ExportPreviewState::default(),
+                delete_state: delete_dialog::DeleteState::default(),
+                trash_entries: Vec::new(),
+                show_trash: false,
+                file_diff: None,
+                plan_state: plan_view::PlanState::default(),
+                settings_state: settings_view::SettingsState::default(),
+                workspaces,
+                workspace_form: workspace_bar::WorkspaceForm::default(),
             },
             Task::perform(
-                async move { load_initial_data(data_dir).await },
-                |(networks, _, _)| Message::NetworksLoaded(networks),
+                async move { load_initial_data(config).await },
+                Message::NetworksLoaded,
             ),
         )
     }
 
     fn update(&mut self, message: Message) -> Task<Message> {
         match message {
-            Message::Toolbar(toolbar_msg) => {
-                self.is_loading = true;
-                let cmd = match toolbar_msg {
-                    toolbar::ToolbarMessage::ImportXml => "import-android",
-                    toolbar::ToolbarMessage::Install => "install",
-                    toolbar::ToolbarMessage::Backup => "backup",
-                    toolbar::ToolbarMessage::Sync => "sync-local",
-                    toolbar::ToolbarMessage::PullAdb => "pull-android",
-                    toolbar::ToolbarMessage::PushAdb => "push-android",
-                };
-                
-                let needs_sudo = matches!(
-                    toolbar_msg,
-                    toolbar::ToolbarMessage::Install | toolbar::ToolbarMessage::Backup
-                );
-                
-                Task::perform(
-                    async move {
-                        let result = if needs_sudo {
-                            script::run_sudo_command_in_terminal(cmd).await
-                        } else {
-                            script::run_command_in_terminal(cmd).await
-                        };
-                        
-                        match result {
-                            Ok(output) => {
-                                if output.success {
-                                    output.stdout
-                                } else {
-                                    format!("{}\\n{}", output.stdout, output.stderr)
-                                }
+            Message::Toolbar(toolbar_msg) => match toolbar_msg {
+                toolbar::ToolbarMessage::Cancel => {
+                    if let Some(handle) = self.script_handle.take() {
+                        let cmd = self.running_command.clone().unwrap_or_default();
+                        self.log(OutputStream::Stderr, &format!("[WARN] Cancelling {}...", cmd));
+                        handle.cancel();
+                    } else if let Some(handle) = self.helper_task.take() {
+                        // Dropping the batch's future stops the helper's process group
+                        handle.abort();
+                        self.log(OutputStream::Stderr, "[WARN] Cancelled the privileged helper");
+                        if let Some(state) = self.password.take() {
+                            state.request.answer(None);
+                        }
+                        if self.plan_state.applying {
+                            self.plan_state.finish(Err("Cancelled".to_string()));
+                        }
+                        self.is_loading = false;
+                        return Task::done(Message::Refresh);
+                    }
+                    self.prompt = None;
+                    Task::none()
+                }
+                toolbar::ToolbarMessage::Settings => Task::perform(
+                    async { config::load_vars() },
+                    Message::SettingsLoaded,
+                ),
+                toolbar::ToolbarMessage::ImportXml => self.load_import_preview(),
+                toolbar::ToolbarMessage::Sync => {
+                    self.is_loading = true;
+                    self.sync_local()
+                }
+                toolbar::ToolbarMessage::Install => self.load_plan(plan::Direction::Install),
+                toolbar::ToolbarMessage::Backup => self.load_plan(plan::Direction::Backup),
+                toolbar::ToolbarMessage::PullAdb => {
+                    // The pulled XML goes through the import preview afterwards
+                    self.is_loading = true;
+                    self.run_script("pull-android", vec!["--no-import".to_string()])
+                }
+                toolbar::ToolbarMessage::PushAdb => {
+                    self.is_loading = true;
+                    // Merge the local networks in first and show the result before pushing
+                    let config = self.config.clone();
+                    Task::perform(
+                        async move { android_export::preview(&config) },
+                        Message::ExportPreviewLoaded,
+                    )
+                }
+            },
+            
+            Message::PlanLoaded(direction, result) => {
+                self.helper_task = None;
+                self.is_loading = false;
+                match result {
+                    Ok(items) => self.plan_state.show(direction, items),
+                    Err(e) => self.log(OutputStream::Stderr, &format!("[ERROR] {}", e)),
+                }
+                Task::none()
+            }
+            
+            Message::Plan(plan_msg) => {
+                match plan_msg {
+                    plan_view::PlanMessage::Close => self.plan_state.hide(),
+                    plan_view::PlanMessage::Apply => {
+                        // Only the selected files, in one privileged helper batch
+                        let config = self.config.clone();
+                        let direction = self.plan_state.direction;
+                        let items = self.plan_state.selected_items();
+                        self.plan_state.applying = true;
+                        self.is_loading = true;
+                        return self.run_helper(Task::perform(
+                            async move { plan::apply(&config, direction, &items).await },
+                            Message::PlanApplied,
+                        ));
+                    }
+                    msg => self.plan_state.update(msg),
+                }
+                Task::none()
+            }
+            
+            Message::ImportPreviewLoaded(result) => {
+                self.is_loading = false;
+                match result {
+                    Ok(networks) => self.import_preview_state.show(networks),
+                    Err(e) => self.log(OutputStream::Stderr, &format!("[ERROR] {}", e)),
+                }
+                Task::none()
+            }
+            
+            Message::ImportPreview(preview_msg) => {
+                let networks = std::mem::take(&mut self.import_preview_state.networks);
+                self.import_preview_state.hide();
+                match preview_msg {
+                    import_preview::ImportPreviewMessage::Confirm => {
+                        self.is_loading = true;
+                        let config = self.config.clone();
+                        Task::perform(
+                            async move { android_import::import(&config, &networks) },
+                            Message::AndroidImported,
+                        )
+                    }
+                    import_preview::ImportPreviewMessage::Cancel => Task::none(),
+                }
+            }
+            
+            Message::AndroidImported(result) => {
+                match result {
+                    Ok(result) => {
+                        for ssid in &result.created {
+                            self.log(OutputStream::Stdout, &format!("[INFO]   + Created: {}", ssid));
+                        }
+                        for (ssid, note) in &result.notes {
+                            self.log(OutputStream::Stderr, &format!("[WARN] {}: {}", ssid, note));
+                        }
+                        for (ssid, reason) in &result.skipped {
+                            self.log(OutputStream::Stderr, &format!("[WARN] Not imported: {} ({})", ssid, reason));
+                        }
+                        self.log(OutputStream::Stdout, &format!(
+                            "[INFO] Imported: {}, Skipped: {}",
+                            result.created.len(),
+                            result.existing.len() + result.skipped.len()
+                        ));
+                        // Add the new networks to the report, like cmd_import_android
+                        return self.sync_local();
+                    }
+                    Err(e) => self.log(OutputStream::Stderr, &format!("[ERROR] {}", e)),
+                }
+                self.is_loading = false;
+                Task::done(Message::Refresh)
+            }
+            
+            Message::ExportPreviewLoaded(result) => {
+                self.is_loading = false;
+                match result {
+                    Ok(result) => self.export_preview_state.show(result),
+                    Err(e) => self.log(OutputStream::Stderr, &format!("[ERROR] {}", e)),
+                }
+                Task::none()
+            }
+            
+            Message::ExportPreview(preview_msg) => {
+                let result = std::mem::take(&mut self.export_preview_state.result);
+                self.export_preview_state.hide();
+                match preview_msg {
+                    export_preview::ExportPreviewMessage::Push => {
+                        if result.has_changes() {
+                            if let Err(e) = android_export::write(&self.config, &result.xml) {
+                                self.log(OutputStream::Stderr, &format!("[ERROR] {}", e));
+                                return Task::none();
                             }
-                            Err(e) => e,
+                            self.log(OutputStream::Stdout, &format!(
+                                "[INFO] Wrote {} added and {} updated networks into {}",
+                                result.added.len(),
+                                result.updated.len(),
+                                self.config.xml_file.display()
+                            ));
                         }
-                    },
-                    Message::CommandComplete,
-                )
+                        self.is_loading = true;
+                        self.run_script("push-android", Vec::new())
+                    }
+                    export_preview::ExportPreviewMessage::Cancel => Task::none(),
+                }
             }
             
             Message::Output(output_msg) => {
                 match output_msg {
                     output_panel::OutputMessage::Copy => {
-                        return clipboard::write(self.terminal_output.clone());
+                        return clipboard::write(output_panel::to_text(&self.terminal_output));
                     }
                     output_panel::OutputMessage::Clear => {
                         self.terminal_output.clear();
@@ -132,6 +370,7 @@ impl WifiManager {
                         self.search_query = query;
                     }
                     network_list::NetworkListMessage::AddNetwork => {
+                        self.edit_network_state.hide();
                         self.add_network_state.show();
                     }
                     network_list::NetworkListMessage::TogglePassword(idx) => {
@@ -141,10 +380,220 @@ impl WifiManager {
                         }
                         self.show_passwords[idx] = !self.show_passwords[idx];
                     }
+                    network_list::NetworkListMessage::EditNetwork(idx) => {
+                        let Some(path) = self.networks.get(idx).and_then(|n| n.path.clone()) else {
+                            return Task::none();
+                        };
+                        match nmconnection::load_settings(&path) {
+                            Ok(settings) => {
+                                self.add_network_state.hide();
+                                self.edit_network_state.show(path, settings);
+                            }
+                            Err(e) => self.log(OutputStream::Stderr, &format!("[ERROR] {}", e)),
+                        }
+                    }
+                    network_list::NetworkListMessage::ShowDiff(idx) => {
+                        let Some(network) = self.networks.get(idx) else {
+                            return Task::none();
+                        };
+                        let filename = network.path
+                            .as_deref()
+                            .and_then(|p| p.file_name())
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_else(|| nmconnection::connection_filename(&network.ssid));
+                        let config = self.config.clone();
+                        self.is_loading = true;
+                        return self.run_helper(Task::perform(
+                            async move { diff::compare(&config, &filename).await },
+                            Message::DiffLoaded,
+                        ));
+                    }
+                    network_list::NetworkListMessage::ToggleSelected(idx, checked) => {
+                        if self.selected.len() <= idx {
+                            self.selected.resize(idx + 1, false);
+                        }
+                        self.selected[idx] = checked;
+                    }
+                    network_list::NetworkListMessage::DeleteSelected => {
+                        let targets: Vec<usize> = self.selected.iter()
+                            .enumerate()
+                            .filter(|(_, s)| **s)
+                            .map(|(idx, _)| idx)
+                            .collect();
+                        self.show_delete_dialog(targets);
+                    }
+                    network_list::NetworkListMessage::DeleteNetwork(idx) => {
+                        self.show_delete_dialog(vec![idx]);
+                    }
+                    network_list::NetworkListMessage::ShowTrash => {
+                        self.show_trash = true;
+                        let config = self.config.clone();
+                        return Task::perform(
+                            async move { trash::list(&config) },
+                            Message::TrashLoaded,
+                        );
+                    }
+                }
+                Task::none()
+            }
+            
+            Message::Delete(delete_msg) => {
+                match delete_msg {
+                    delete_dialog::DeleteMessage::ForgetToggled(forget) => {
+                        self.delete_state.forget = forget;
+                    }
+                    delete_dialog::DeleteMessage::Cancel => self.delete_state.hide(),
+                    delete_dialog::DeleteMessage::Confirm => {
+                        let config = self.config.clone();
+                        let forget = self.delete_state.forget;
+                        let targets: Vec<Network> = self.delete_state.targets.iter()
+                            .filter_map(|idx| self.networks.get(*idx).cloned())
+                            .collect();
+                        
+                        self.delete_state.hide();
+                        self.is_loading = true;
+                        
+                        return Task::perform(
+                            async move {
+                                targets.iter()
+                                    .map(|n| {
+                                        trash::move_to_trash(&config, n)
+                                            .map_err(|e| format!("Failed to delete {}: {}", n.ssid, e))
+                                    })
+                                    .collect::<Vec<_>>()
+                            },
+                            move |result| Message::NetworksDeleted(result, forget),
+                        );
+                    }
+                }
+                Task::none()
+            }
+            
+            Message::NetworksDeleted(results, forget) => {
+                let mut entries = Vec::new();
+                for result in results {
+                    match result {
+                        Ok(entry) => entries.push(entry),
+                        Err(e) => self.log(OutputStream::Stderr, &format!("[ERROR] {}", e)),
+                    }
+                }
+                if !entries.is_empty() {
+                    let ssids: Vec<&str> = entries.iter().map(|e| e.ssid.as_str()).collect();
+                    self.log(OutputStream::Stdout, &format!("[INFO] Moved to trash: {}", ssids.join(", ")));
+                }
+                // Forget the ones that made it to the trash, even if others failed
+                if forget && !entries.is_empty() {
+                    let config = self.config.clone();
+                    return self.run_helper(Task::perform(
+                        async move { trash::forget(&config, &entries).await },
+                        Message::NetworksForgotten,
+                    ));
+                }
+                Task::done(Message::Refresh)
+            }
+            
+            Message::NetworksForgotten(result) => {
+                self.helper_task = None;
+                match result {
+                    Ok(ssids) if ssids.is_empty() => self.log(OutputStream::Stdout, "[INFO] None of them were installed"),
+                    Ok(ssids) => {
+                        self.log(OutputStream::Stdout, &format!("[INFO] Forgotten from the system: {}", ssids.join(", ")));
+                        self.last_counts = Some(format!("forget: {} forgotten", ssids.len()));
+                        for ssid in ssids {
+                            self.row_actions.insert(ssid, Action::Forgotten);
+                        }
+                    }
+                    Err(e) => self.log(OutputStream::Stderr, &format!("[ERROR] Forget failed: {}", e)),
+                }
+                Task::done(Message::Refresh)
+            }
+            
+            Message::PlanApplied(result) => {
+                self.helper_task = None;
+                let direction = self.plan_state.direction;
+                let applied = match &result {
+                    Ok(applied) => applied.clone(),
+                    Err(_) => Vec::new(),
+                };
+                if let Err(e) = &result {
+                    self.log(OutputStream::Stderr, &format!("[ERROR] {} failed: {}", direction.command(), e));
+                }
+                self.plan_state.finish(result);
+                if applied.is_empty() {
+                    self.is_loading = false;
+                    return Task::none();
+                }
+                
+                // Regenerate the report like the CLI does after install/backup
+                let sync = self.sync_local();
+                let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+                for (file, action) in &applied {
+                    self.log(OutputStream::Stdout, &format!("[INFO] {}: {}", action, file));
+                    *counts.entry(action.to_string()).or_default() += 1;
+                    let ssid = self.ssid_for_file(file);
+                    self.row_actions.insert(ssid, *action);
+                }
+                let summary: Vec<String> = counts.iter().map(|(name, count)| format!("{} {}", count, name)).collect();
+                self.last_counts = Some(format!("{}: {}", direction.command(), summary.join(", ")));
+                sync
+            }
+            
+            Message::Trash(trash_msg) => {
+                match trash_msg {
+                    trash_view::TrashMessage::Close => self.show_trash = false,
+                    trash_view::TrashMessage::Restore(idx) => {
+                        if let Some(entry) = self.trash_entries.get(idx).cloned() {
+                            let config = self.config.clone();
+                            self.is_loading = true;
+                            return Task::perform(
+                                async move { trash::restore(&config, &entry) },
+                                Message::NetworkRestored,
+                            );
+                        }
+                    }
+                }
+                Task::none()
+            }
+            
+            Message::Diff(diff_view::DiffMessage::Close) => {
+                self.file_diff = None;
+                Task::none()
+            }
+            
+            Message::DiffLoaded(result) => {
+                self.helper_task = None;
+                self.is_loading = false;
+                match result {
+                    Ok(file_diff) => self.file_diff = Some(file_diff),
+                    Err(e) => self.log(OutputStream::Stderr, &format!("[ERROR] {}", e)),
                 }
                 Task::none()
             }
             
+            Message::TrashLoaded(entries) => {
+                self.trash_entries = entries;
+                Task::none()
+            }
+            
+            Message::NetworkRestored(result) => {
+                let config = self.config.clone();
+                let reload_trash = Task::perform(
+                    async move { trash::list(&config) },
+                    Message::TrashLoaded,
+                );
+                match result {
+                    Ok(path) => {
+                        self.log(OutputStream::Stdout, &format!("[INFO] Restored: {}", path.display()));
+                        Task::batch([reload_trash, self.sync_local()])
+                    }
+                    Err(e) => {
+                        self.log(OutputStream::Stderr, &format!("[ERROR] {}", e));
+                        self.is_loading = false;
+                        reload_trash
+                    }
+                }
+            }
+            
             Message::AddNetwork(dialog_msg) => {
                 match dialog_msg {
                     add_network_dialog::AddNetworkMessage::SsidChanged(s) => {
@@ -156,21 +605,29 @@ impl WifiManager {
                     add_network_dialog::AddNetworkMessage::SecurityChanged(sec) => {
                         self.add_network_state.security = sec;
                     }
+                    add_network_dialog::AddNetworkMessage::Enterprise(msg) => {
+                        enterprise_form::update(&mut self.add_network_state.enterprise, msg);
+                    }
                     add_network_dialog::AddNetworkMessage::Cancel => {
                         self.add_network_state.hide();
                     }
                     add_network_dialog::AddNetworkMessage::Submit => {
-                        let data_dir = self.data_dir.clone();
+                        if let Err(e) = self.add_network_state.validate() {
+                            self.add_network_state.error = Some(e);
+                            return Task::none();
+                        }
+                        let config = self.config.clone();
                         let ssid = self.add_network_state.ssid.clone();
                         let password = self.add_network_state.password.clone();
                         let security = self.add_network_state.security.clone();
+                        let enterprise = self.add_network_state.enterprise.clone();
                         
                         self.add_network_state.hide();
                         self.is_loading = true;
                         
                         return Task::perform(
                             async move {
-                                nmconnection::create_network(&data_dir, &ssid, &password, &security)
+                                nmconnection::create_network(&config, &ssid, &password, &security, &enterprise)
                             },
                             Message::NetworkCreated,
                         );
@@ -179,73 +636,295 @@ impl WifiManager {
                 Task::none()
             }
             
-            Message::NetworkCreated(result) => {
-                match result {
-                    Ok(()) => {
-                        self.terminal_output.push_str("[INFO] Network created successfully\n");
-                        // Trigger sync
+            Message::EditNetwork(dialog_msg) => {
+                let state = &mut self.edit_network_state;
+                match dialog_msg {
+                    edit_network_dialog::EditNetworkMessage::SsidChanged(s) => state.ssid = s,
+                    edit_network_dialog::EditNetworkMessage::PasswordChanged(p) => state.password = p,
+                    edit_network_dialog::EditNetworkMessage::SecurityChanged(sec) => state.security = sec,
+                    edit_network_dialog::EditNetworkMessage::Enterprise(msg) => enterprise_form::update(&mut state.enterprise, msg),
+                    edit_network_dialog::EditNetworkMessage::HiddenToggled(b) => state.hidden = b,
+                    edit_network_dialog::EditNetworkMessage::AutoconnectToggled(b) => state.autoconnect = b,
+                    edit_network_dialog::EditNetworkMessage::PriorityChanged(p) => state.priority = p,
+                    edit_network_dialog::EditNetworkMessage::Cancel => state.hide(),
+                    edit_network_dialog::EditNetworkMessage::Submit => {
+                        let updated = match state.settings() {
+                            Ok(settings) => settings,
+                            Err(e) => {
+                                state.error = Some(e);
+                                return Task::none();
+                            }
+                        };
+                        let Some(original) = state.original.clone() else {
+                            return Task::none();
+                        };
+                        let config = self.config.clone();
+                        let path = state.path.clone();
+                        
+                        state.hide();
                         self.is_loading = true;
+                        
                         return Task::perform(
                             async move {
-                                script::run_command("sync-local").await
-                            },
-                            |result| {
-                                Message::CommandComplete(
-                                    result.map(|o| o.stdout).unwrap_or_else(|e| e)
-                                )
+                                nmconnection::update_network(&config, &path, &original, &updated)
                             },
+                            Message::NetworkUpdated,
                         );
                     }
+                }
+                Task::none()
+            }
+            
+            Message::NetworkUpdated(result) => {
+                match result {
+                    Ok(path) => {
+                        self.log(OutputStream::Stdout, &format!("[INFO] Network updated: {}", path.display()));
+                        self.is_loading = true;
+                        return self.sync_local();
+                    }
+                    Err(e) => {
+                        self.log(OutputStream::Stderr, &format!("[ERROR] {}", e));
+                        self.is_loading = false;
+                    }
+                }
+                Task::none()
+            }
+            
+            Message::NetworkCreated(result) => {
+                match result {
+                    Ok(()) => {
+                        self.log(OutputStream::Stdout, "[INFO] Network created successfully");
+                        // Trigger sync
+                        self.is_loading = true;
+                        return self.sync_local();
+                    }
                     Err(e) => {
-                        self.terminal_output.push_str(&format!("[ERROR] {}\n", e));
+                        self.log(OutputStream::Stderr, &format!("[ERROR] {}", e));
                         self.is_loading = false;
                     }
                 }
                 Task::none()
             }
             
-            Message::CommandComplete(output) => {
-                self.terminal_output.push_str(&output);
-                self.terminal_output.push('\n');
+            Message::Synced(result) => {
+                match result {
+                    Ok(changes) => self.show_sync_changes(changes),
+                    Err(e) => self.log(OutputStream::Stderr, &format!("[ERROR] Sync failed: {}", e)),
+                }
+                Task::done(Message::Refresh)
+            }
+            
+            Message::Script(ScriptEvent::Line(stream, line)) => {
+                self.terminal_output.push(output_panel::OutputLine { stream, level: None, text: line });
+                Task::none()
+            }
+            
+            Message::Script(ScriptEvent::Event(event)) => {
+                self.handle_event(event);
+                Task::none()
+            }
+            
+            Message::Script(ScriptEvent::Prompt(prompt)) => {
+                self.prompt = Some(prompt);
+                Task::none()
+            }
+            
+            Message::Prompt(prompt_msg) => {
+                let yes = matches!(prompt_msg, prompt_dialog::PromptMessage::Yes);
+                if let (Some(prompt), Some(handle)) = (self.prompt.take(), &self.script_handle) {
+                    handle.answer(yes);
+                    self.log(OutputStream::Stdout, &format!("> {} {}", prompt.question, if yes { "y" } else { "n" }));
+                }
+                Task::none()
+            }
+            
+            Message::Askpass(request) => {
+                // sudo asks again after a wrong password; drop an older prompt
+                if let Some(old) = self.password.take() {
+                    old.request.answer(None);
+                }
+                self.password = Some(password_dialog::PasswordState::new(request));
+                password_dialog::PasswordState::focus()
+            }
+            
+            Message::Password(password_msg) => {
+                match password_msg {
+                    password_dialog::PasswordMessage::Changed(password) => {
+                        if let Some(state) = &mut self.password {
+                            state.password = password;
+                        }
+                    }
+                    password_dialog::PasswordMessage::Submit => {
+                        if let Some(state) = self.password.take() {
+                            state.request.answer(Some(state.password));
+                        }
+                    }
+                    password_dialog::PasswordMessage::Cancel => {
+                        if let Some(state) = self.password.take() {
+                            state.request.answer(None);
+                        }
+                    }
+                }
+                Task::none()
+            }
+            
+            Message::Script(ScriptEvent::Finished(result)) => {
+                let cmd = self.running_command.take().unwrap_or_default();
+                self.script_handle = None;
+                self.prompt = None;
+                self.last_result = match result {
+                    Ok(output) if output.success => None,
+                    Ok(output) => Some(match output.status {
+                        ScriptStatus::Exited => match output.code {
+                            Some(code) => format!("{} failed (exit code {})", cmd, code),
+                            None => format!("{} was killed", cmd),
+                        },
+                        ScriptStatus::Cancelled => format!("{} cancelled", cmd),
+                        ScriptStatus::TimedOut => format!("{} timed out", cmd),
+                    }),
+                    Err(e) => {
+                        self.log(OutputStream::Stderr, &format!("[ERROR] {}", e));
+                        Some(format!("{} could not be started", cmd))
+                    }
+                };
+                if let Some(result) = self.last_result.clone() {
+                    self.log(OutputStream::Stderr, &format!("[ERROR] {}", result));
+                }
                 self.is_loading = false;
                 
                 // Refresh networks after command
-                let data_dir = self.data_dir.clone();
-                Task::perform(
-                    async move {
-                        let networks = nmconnection::load_networks(&data_dir.join("network_connections"));
-                        let (is_synced, count) = nmconnection::check_sync_status(&data_dir);
-                        (networks, is_synced, count)
-                    },
-                    |(networks, _, _)| Message::NetworksLoaded(networks),
-                )
+                let config = self.config.clone();
+                let refresh = Task::perform(
+                    async move { nmconnection::load_networks(&config.local_dir) },
+                    Message::NetworksLoaded,
+                );
+                if cmd == "pull-android" && self.last_result.is_none() {
+                    return Task::batch([refresh, self.load_import_preview()]);
+                }
+                refresh
             }
             
             Message::NetworksLoaded(networks) => {
                 self.show_passwords = vec![false; networks.len()];
+                self.selected = vec![false; networks.len()];
                 self.networks = networks;
                 self.is_loading = false;
                 
-                let data_dir = self.data_dir.clone();
+                let config = self.config.clone();
                 Task::perform(
-                    async move {
-                        nmconnection::check_sync_status(&data_dir)
-                    },
-                    |(is_synced, count)| Message::SyncStatusChecked(is_synced, count),
+                    async move { sync::status(&config) },
+                    Message::SyncStatusChecked,
                 )
             }
             
-            Message::SyncStatusChecked(is_synced, count) => {
-                self.is_synced = is_synced;
-                self.network_count = count;
+            Message::SyncStatusChecked(status) => {
+                if status.is_synced() {
+                    self.show_sync_details = false;
+                }
+                self.sync_status = status;
+                Task::none()
+            }
+            
+            Message::SyncStatus(status_msg) => {
+                self.show_sync_details = match status_msg {
+                    sync_status::SyncStatusMessage::Toggle => !self.show_sync_details,
+                    sync_status::SyncStatusMessage::Close => false,
+                };
                 Task::none()
             }
             
+            Message::SettingsLoaded(vars) => {
+                self.settings_state.show(&vars);
+                Task::none()
+            }
+            
+            Message::Settings(settings_msg) => {
+                match settings_msg {
+                    settings_view::SettingsMessage::Close => self.settings_state.hide(),
+                    settings_view::SettingsMessage::Save => {
+                        let changes = self.settings_state.changes();
+                        return Task::perform(
+                            async move { config::save_user_settings(&changes) },
+                            Message::SettingsSaved,
+                        );
+                    }
+                    msg => self.settings_state.update(msg),
+                }
+                Task::none()
+            }
+            
+            Message::SettingsSaved(result) => {
+                match result {
+                    Ok(()) => {
+                        self.settings_state.hide();
+                        self.log(OutputStream::Stdout, "[INFO] Settings saved");
+                        // The default workspace follows config.env
+                        let active = self.workspaces.active.clone();
+                        self.workspaces = Workspaces::load();
+                        self.workspaces.active = active;
+                        // Apply without restarting: reload everything from the new directories
+                        self.apply_workspace()
+                    }
+                    Err(e) => {
+                        self.settings_state.error = Some(e);
+                        Task::none()
+                    }
+                }
+            }
+            
+            Message::Workspace(workspace_msg) => {
+                use workspace_bar::WorkspaceMessage;
+                
+                let result = match workspace_msg {
+                    WorkspaceMessage::Selected(name) => {
+                        if self.is_loading || name == self.workspaces.active {
+                            return Task::none();
+                        }
+                        self.workspaces.select(&name)
+                    }
+                    WorkspaceMessage::ShowAdd => {
+                        self.workspace_form.show();
+                        return Task::none();
+                    }
+                    WorkspaceMessage::NameChanged(name) => {
+                        self.workspace_form.name = name;
+                        return Task::none();
+                    }
+                    WorkspaceMessage::DirChanged(dir) => {
+                        self.workspace_form.dir = dir;
+                        return Task::none();
+                    }
+                    WorkspaceMessage::CancelAdd => {
+                        self.workspace_form.hide();
+                        return Task::none();
+                    }
+                    WorkspaceMessage::Add => {
+                        match self.workspaces.add(&self.workspace_form.name, &self.workspace_form.dir) {
+                            Ok(()) => {
+                                self.workspace_form.hide();
+                                Ok(())
+                            }
+                            Err(e) => {
+                                self.workspace_form.error = Some(e);
+                                return Task::none();
+                            }
+                        }
+                    }
+                    WorkspaceMessage::RemoveActive => self.workspaces.remove_active(),
+                };
+                
+                if let Err(e) = result {
+                    // The switch still applies for this session
+                    self.log(OutputStream::Stderr, &format!("[WARN] Could not save workspaces: {}", e));
+                }
+                self.apply_workspace()
+            }
+            
             Message::Refresh => {
-                let data_dir = self.data_dir.clone();
+                let config = self.config.clone();
                 Task::perform(
                     async move {
-                        nmconnection::load_networks(&data_dir.join("network_connections"))
+                        nmconnection::load_networks(&config.local_dir)
                     },
                     Message::NetworksLoaded,
                 )
@@ -253,36 +932,258 @@ impl WifiManager {
         }
     }
 
+    /// Point everything at the active workspace's directories and reload
+    fn apply_workspace(&mut self) -> Task<Message> {
+        self.config = self.workspaces.config();
+        self.log(
+            OutputStream::Stdout,
+            &format!("[INFO] Workspace {}: {}", self.workspaces.active, self.config.base_dir.display()),
+        );
+        self.trash_entries.clear();
+        self.show_trash = false;
+        self.file_diff = None;
+        self.plan_state.hide();
+        self.import_preview_state.hide();
+        self.export_preview_state.hide();
+        self.row_actions.clear();
+        self.last_counts = None;
+        self.is_loading = true;
+        Task::done(Message::Refresh)
+    }
+    
+    /// Run a wifi-manager.sh command, streaming its output into the terminal panel
+    fn run_script(&mut self, cmd: &str, args: Vec<String>) -> Task<Message> {
+        let (events, handle) = script::stream_command(
+            cmd,
+            &args,
+            &self.config.base_dir,
+            self.config.command_timeout(cmd),
+        );
+        self.running_command = Some(cmd.to_string());
+        self.script_handle = Some(handle);
+        self.last_result = None;
+        self.last_counts = None;
+        self.row_actions.clear();
+        Task::run(events, Message::Script)
+    }
+    
+    /// Parse the Android XML natively so the user can review what will be imported
+    fn load_import_preview(&mut self) -> Task<Message> {
+        self.is_loading = true;
+        let config = self.config.clone();
+        Task::perform(
+            async move { android_import::load(&config) },
+            Message::ImportPreviewLoaded,
+        )
+    }
+    
+    /// Compute what install or backup would copy, for review before applying
+    fn load_plan(&mut self, direction: plan::Direction) -> Task<Message> {
+        self.is_loading = true;
+        let config = self.config.clone();
+        self.run_helper(Task::perform(
+            async move { plan::plan(&config, direction).await },
+            move |result| Message::PlanLoaded(direction, result),
+        ))
+    }
+    
+    /// Make a task that runs the privileged helper cancellable from the toolbar
+    fn run_helper(&mut self, task: Task<Message>) -> Task<Message> {
+        let (task, handle) = task.abortable();
+        self.helper_task = Some(handle);
+        task
+    }
+    
+    /// Show a --json event in the terminal and record what it says about networks
+    fn handle_event(&mut self, event: JsonEvent) {
+        if event.level != Level::Debug {
+            self.log_level(event.level, event.message.trim_start());
+        }
+
+        if let Some(action) = event.action.filter(|_| !event.dry_run) {
+            // Install/backup only know the file; map it back to the network
+            let ssid = event.ssid.clone()
+                .filter(|s| !s.is_empty())
+                .or_else(|| event.file.as_deref().map(|file| self.ssid_for_file(file)));
+            if let Some(ssid) = ssid {
+                self.row_actions.insert(ssid, action);
+            }
+        }
+
+        if let Some(counts) = event.counts {
+            let summary = counts
+                .iter()
+                .map(|(name, count)| format!("{} {}", count, name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            self.last_counts = Some(format!("{}: {}", event.operation, summary));
+        }
+    }
+    
+    /// SSID of the network stored in `file`, or the filename without extension
+    fn ssid_for_file(&self, file: &str) -> String {
+        self.networks
+            .iter()
+            .find(|n| n.path.as_deref().and_then(|p| p.file_name()).is_some_and(|f| f == file))
+            .map(|n| n.ssid.clone())
+            .unwrap_or_else(|| file.trim_end_matches(".nmconnection").to_string())
+    }
+    
+    /// Sync the report and the connection files in-process after a local change
+    fn sync_local(&mut self) -> Task<Message> {
+        self.last_counts = None;
+        self.row_actions.clear();
+        let config = self.config.clone();
+        Task::perform(
+            async move { sync::sync(&config) },
+            Message::Synced,
+        )
+    }
+    
+    /// Log a sync change set and mark the affected rows
+    fn show_sync_changes(&mut self, changes: sync::SyncChanges) {
+        if changes.is_empty() {
+            self.log(OutputStream::Stdout, "[INFO] ✓ Report and local connections already in sync");
+            return;
+        }
+        
+        let file_changes = [
+            (&changes.created, Action::Created, Level::Info, "+ Created from report"),
+            (&changes.passwords_injected, Action::Updated, Level::Info, "~ Password injected from report"),
+            (&changes.skipped_enterprise, Action::Skipped, Level::Warn, "! Cannot recreate enterprise network from report"),
+        ];
+        for (ssids, action, level, label) in file_changes {
+            for ssid in ssids {
+                self.log_level(level, &format!("{}: {}", label, ssid));
+                self.row_actions.insert(ssid.clone(), action);
+            }
+        }
+        
+        let report_changes = [
+            (&changes.report_added, "added"),
+            (&changes.report_changed, "changed"),
+            (&changes.report_removed, "removed"),
+        ];
+        for (ssids, label) in report_changes {
+            if !ssids.is_empty() {
+                self.log_level(Level::Info, &format!("Report rows {}: {}", label, ssids.join(", ")));
+            }
+        }
+        
+        let counts = [
+            (changes.created.len(), "created"),
+            (changes.passwords_injected.len(), "updated"),
+            (changes.skipped_enterprise.len(), "skipped"),
+            (changes.report_added.len(), "rows added"),
+            (changes.report_changed.len(), "rows changed"),
+            (changes.report_removed.len(), "rows removed"),
+        ];
+        let summary: Vec<String> = counts
+            .iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, label)| format!("{} {}", count, label))
+            .collect();
+        self.last_counts = Some(format!("sync-local: {}", summary.join(", ")));
+    }
+    
+    /// Append a `[LEVEL] message` line, coloured by level
+    fn log_level(&mut self, level: Level, text: &str) {
+        let stream = if level == Level::Error { OutputStream::Stderr } else { OutputStream::Stdout };
+        self.terminal_output.push(output_panel::OutputLine {
+            stream,
+            level: Some(level),
+            text: format!("[{}] {}", level, text),
+        });
+    }
+    
+    /// Append GUI-side messages to the terminal panel
+    fn log(&mut self, stream: OutputStream, text: &str) {
+        self.terminal_output.extend(text.lines().map(|line| output_panel::OutputLine {
+            stream,
+            level: None,
+            text: line.to_string(),
+        }));
+    }
+
+    fn show_delete_dialog(&mut self, targets: Vec<usize>) {
+        let ssids = targets.iter()
+            .filter_map(|idx| self.networks.get(*idx).map(|n| n.ssid.clone()))
+            .collect();
+        self.add_network_state.hide();
+        self.edit_network_state.hide();
+        self.delete_state.show(targets, ssids);
+    }
+
     fn view(&self) -> Element<'_, Message> {
         // Status bar with loading indicator
-        let status_text = if self.is_loading {
+        let mut status_text = if let Some(cmd) = &self.running_command {
+            format!("⏳ Running {}...", cmd)
+        } else if self.is_loading {
             "⏳ Loading...".to_string()
-        } else if self.is_synced {
-            format!("✓ Synced ({} networks)", self.network_count)
         } else {
-            format!("⚠ Not synced ({} networks)", self.network_count)
+            self.sync_status.summary()
         };
-        let status_bar = container(text(status_text).size(14))
-            .padding(10)
-            .width(Length::Fill);
+        if let Some(counts) = &self.last_counts {
+            status_text.push_str(&format!("  |  {}", counts));
+        }
+        if let Some(result) = &self.last_result {
+            status_text.push_str(&format!("  |  ✖ {}", result));
+        }
+        let workspace_bar = workspace_bar::view(&self.workspaces, &self.workspace_form, !self.is_loading)
+            .map(Message::Workspace);
+        let status_bar = container(
+            row![
+                text(status_text).size(14),
+                button(text("Details").size(12))
+                    .on_press(Message::SyncStatus(sync_status::SyncStatusMessage::Toggle))
+                    .padding(4),
+                horizontal_space(),
+                workspace_bar,
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        )
+        .padding(10)
+        .width(Length::Fill);
         
         // Toolbar (disabled during loading)
-        let toolbar = toolbar::view(self.is_loading).map(Message::Toolbar);
+        let toolbar = toolbar::view(self.is_loading, self.script_handle.is_some() || self.helper_task.is_some()).map(Message::Toolbar);
         
         // Main content: network list (left) | output panel (right)
         let network_list: Element<Message> = network_list::view(
             &self.networks, 
             &self.search_query,
             &self.show_passwords,
+            &self.selected,
+            &self.row_actions,
         ).map(Message::NetworkList);
         
         let output_panel = output_panel::view(&self.terminal_output).map(Message::Output);
         
         // Add network dialog (overlay)
         let add_dialog = add_network_dialog::view(&self.add_network_state).map(Message::AddNetwork);
+        let edit_dialog = edit_network_dialog::view(&self.edit_network_state).map(Message::EditNetwork);
+        let delete_dialog = delete_dialog::view(&self.delete_state).map(Message::Delete);
+        
+        // Import preview replaces the list while it is open
+        let left_panel: Element<Message> = if self.settings_state.visible {
+            settings_view::view(&self.settings_state).map(Message::Settings)
+        } else if self.import_preview_state.visible {
+            import_preview::view(&self.import_preview_state).map(Message::ImportPreview)
+        } else if self.export_preview_state.visible {
+            export_preview::view(&self.export_preview_state).map(Message::ExportPreview)
+        } else if self.plan_state.visible {
+            plan_view::view(&self.plan_state).map(Message::Plan)
+        } else if self.show_trash {
+            trash_view::view(&self.trash_entries).map(Message::Trash)
+        } else if let Some(file_diff) = &self.file_diff {
+            diff_view::view(file_diff).map(Message::Diff)
+        } else {
+            column![network_list, add_dialog, edit_dialog, delete_dialog].into()
+        };
         
         let main_content = row![
-            container(column![network_list, add_dialog])
+            container(left_panel)
                 .width(Length::FillPortion(2))
                 .height(Length::Fill)
                 .padding(10),
@@ -294,35 +1195,42 @@ impl WifiManager {
         .spacing(10);
         
         // Full layout
-        column![
+        let layout: Element<Message> = column![
             status_bar,
             toolbar,
             main_content,
         ]
         .spacing(5)
-        .into()
-    }
-}
-
-fn get_data_dir() -> PathBuf {
-    // Try to find data directory relative to executable
-    if let Ok(exe_path) = std::env::current_exe() {
-        let mut path = exe_path;
-        path.pop(); // binary
-        path.pop(); // release/debug
-        path.pop(); // target
-        path.push("data");
-        if path.exists() {
-            return path;
+        .into();
+        
+        let layout = if self.show_sync_details {
+            let details = sync_status::details(&self.sync_status).map(Message::SyncStatus);
+            sync_status::popover(layout, details)
+        } else {
+            layout
+        };
+        
+        let layout = match &self.prompt {
+            Some(prompt) => prompt_dialog::modal(layout, prompt_dialog::view(prompt).map(Message::Prompt)),
+            None => layout,
+        };
+        
+        match &self.password {
+            Some(state) => prompt_dialog::modal(layout, password_dialog::view(state).map(Message::Password)),
+            None => layout,
         }
     }
     
-    // Fallback to current directory
-    PathBuf::from("../data")
+    /// sudo's password prompts, only listened for when sudo is the privilege method
+    fn subscription(&self) -> Subscription<Message> {
+        if self.config.privilege == Some(Method::Sudo) {
+            Subscription::run(askpass::listen).map(Message::Askpass)
+        } else {
+            Subscription::none()
+        }
+    }
 }
 
-async fn load_initial_data(data_dir: PathBuf) -> (Vec<Network>, bool, usize) {
-    let networks = nmconnection::load_networks(&data_dir.join("network_connections"));
-    let (is_synced, count) = nmconnection::check_sync_status(&data_dir);
-    (networks, is_synced, count)
+async fn load_initial_data(config: Config) -> Vec<Network> {
+    nmconnection::load_networks(&config.local_dir)
 }
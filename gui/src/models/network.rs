@@ -1,15 +1,152 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+
+/// Wi-Fi security mode of a connection profile
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Security {
+    #[default]
+    Open,
+    /// Opportunistic Wireless Encryption (Enhanced Open)
+    Owe,
+    /// Static WEP (`key-mgmt=none`)
+    Wep,
+    /// WPA/WPA2 Personal
+    WpaPsk,
+    /// WPA2/WPA3 Personal transition mode (`wpa-psk` with optional PMF)
+    Transition,
+    /// WPA3 Personal
+    Sae,
+    /// WPA/WPA2/WPA3 Enterprise (802.1X)
+    Enterprise,
+    /// Unrecognised `key-mgmt` value
+    Unknown(String),
+}
+
+impl Security {
+    /// Modes offered in the add/edit dialogs
+    pub const SELECTABLE: &'static [Security] = &[
+        Security::WpaPsk,
+        Security::Transition,
+        Security::Sae,
+        Security::Enterprise,
+        Security::Owe,
+        Security::Wep,
+        Security::Open,
+    ];
+
+    /// Classify a `[wifi-security]` section; `get` looks up a key in that section.
+    /// Mirrors `parse_nmconnection_file` in lib/nmconnection.sh.
+    pub fn detect<'a>(get: impl Fn(&str) -> Option<&'a str>) -> Self {
+        let key_mgmt = get("key-mgmt").unwrap_or_default();
+        let proto = get("proto").unwrap_or_default();
+        let pairwise = get("pairwise").unwrap_or_default();
+        let pmf = get("pmf").unwrap_or_default();
+
+        match key_mgmt {
+            "sae" => Security::Sae,
+            "owe" => Security::Owe,
+            "wpa-eap" | "wpa-eap-suite-b-192" | "ieee8021x" => Security::Enterprise,
+            "wpa-psk" if pmf == "2" && proto != "wpa" && pairwise != "tkip" => Security::Transition,
+            "wpa-psk" => Security::WpaPsk,
+            "none" => Security::Wep,
+            "" if get("wep-key-type").is_some() || get("wep-key0").is_some() => Security::Wep,
+            "" if get("psk").is_some() => Security::WpaPsk,
+            "" => Security::Open,
+            other => Security::Unknown(other.to_string()),
+        }
+    }
+
+    /// NetworkManager `key-mgmt` value, `None` for open networks
+    pub fn key_mgmt(&self) -> Option<&str> {
+        match self {
+            Security::Open => None,
+            Security::Owe => Some("owe"),
+            Security::Wep => Some("none"),
+            Security::WpaPsk | Security::Transition => Some("wpa-psk"),
+            Security::Sae => Some("sae"),
+            Security::Enterprise => Some("wpa-eap"),
+            Security::Unknown(v) => Some(v),
+        }
+    }
+
+    /// Parse a report label. Like `create_nmconnection`, a missing or `Unknown`
+    /// label means WPA/WPA2 when there is a password and open otherwise.
+    pub fn from_label(label: &str, has_password: bool) -> Self {
+        match label {
+            "OWE" => Security::Owe,
+            "WEP" => Security::Wep,
+            "WPA/WPA2" => Security::WpaPsk,
+            "WPA2/WPA3" => Security::Transition,
+            "WPA3" => Security::Sae,
+            "Enterprise" => Security::Enterprise,
+            "" | "Unknown" if has_password => Security::WpaPsk,
+            _ => Security::Open,
+        }
+    }
+
+    /// Label written to the report; unknown modes are just `Unknown` as in the CLI
+    pub fn report_label(&self) -> String {
+        match self {
+            Security::Unknown(_) => "Unknown".to_string(),
+            other => other.to_string(),
+        }
+    }
+
+    /// Whether the mode needs a password/key to be stored in the profile
+    pub fn requires_password(&self) -> bool {
+        matches!(self, Security::Wep | Security::WpaPsk | Security::Transition | Security::Sae)
+    }
+}
+
+impl fmt::Display for Security {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Security::Open => f.write_str("Open"),
+            Security::Owe => f.write_str("OWE"),
+            Security::Wep => f.write_str("WEP"),
+            Security::WpaPsk => f.write_str("WPA/WPA2"),
+            Security::Transition => f.write_str("WPA2/WPA3"),
+            Security::Sae => f.write_str("WPA3"),
+            Security::Enterprise => f.write_str("Enterprise"),
+            Security::Unknown(v) => write!(f, "Unknown ({})", v),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Network {
     pub ssid: String,
-    pub security: String,
+    pub security: Security,
     pub password: Option<String>,
+    /// 802.1X settings for enterprise networks
+    pub enterprise: Option<EnterpriseSettings>,
+    /// Source .nmconnection file, if loaded from disk
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
 }
 
 impl Network {
-    pub fn new(ssid: String, security: String, password: Option<String>) -> Self {
-        Self { ssid, security, password }
+    pub fn new(ssid: String, security: Security, password: Option<String>) -> Self {
+        Self { ssid, security, password, enterprise: None, path: None }
+    }
+
+    pub fn with_enterprise(mut self, enterprise: EnterpriseSettings) -> Self {
+        self.enterprise = Some(enterprise);
+        self
+    }
+
+    /// Security label for the list, including the EAP method for enterprise networks
+    pub fn security_display(&self) -> String {
+        match &self.enterprise {
+            Some(e) if !e.eap.is_empty() => format!("{} ({})", self.security, e.eap.to_uppercase()),
+            _ => self.security.to_string(),
+        }
+    }
+
+    pub fn with_path(mut self, path: PathBuf) -> Self {
+        self.path = Some(path);
+        self
     }
 
     pub fn password_display(&self) -> String {
@@ -19,3 +156,152 @@ impl Network {
         }
     }
 }
+
+/// Editable settings of an existing connection profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkSettings {
+    pub ssid: String,
+    pub password: String,
+    pub security: Security,
+    pub hidden: bool,
+    pub autoconnect: bool,
+    pub priority: i32,
+    pub metered: Metered,
+    pub mac_address: MacAddress,
+    pub enterprise: EnterpriseSettings,
+}
+
+/// `connection.metered`; Android's `MeteredOverride` uses the same numbers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Metered {
+    /// Not set, left to NetworkManager's guess
+    #[default]
+    Unknown,
+    Yes,
+    No,
+}
+
+impl Metered {
+    /// Parse the keyfile value, written as a number but also accepted as yes/no
+    pub fn from_keyfile(value: Option<&str>) -> Self {
+        match value {
+            Some("1" | "yes" | "true") => Metered::Yes,
+            Some("2" | "no" | "false") => Metered::No,
+            _ => Metered::Unknown,
+        }
+    }
+
+    /// Keyfile value, `None` to leave the key out
+    pub fn keyfile_value(self) -> Option<&'static str> {
+        match self {
+            Metered::Unknown => None,
+            Metered::Yes => Some("1"),
+            Metered::No => Some("2"),
+        }
+    }
+}
+
+impl fmt::Display for Metered {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Metered::Unknown => "auto",
+            Metered::Yes => "yes",
+            Metered::No => "no",
+        })
+    }
+}
+
+/// Which MAC address the profile connects with
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum MacAddress {
+    /// Not set, NetworkManager's default applies
+    #[default]
+    Default,
+    /// The hardware address
+    Permanent,
+    /// A random address that stays the same for this network
+    Stable,
+    /// A new random address on every connection
+    Random,
+    /// Any other `cloned-mac-address` value (a fixed address, `preserve`...), kept as is
+    Other(String),
+}
+
+impl MacAddress {
+    /// Read `wifi.cloned-mac-address`, falling back to the deprecated
+    /// `mac-address-randomization` (1 = never, 2 = always)
+    pub fn from_keyfile(cloned: Option<&str>, randomization: Option<&str>) -> Self {
+        match (cloned, randomization) {
+            (Some("permanent"), _) => MacAddress::Permanent,
+            (Some("stable"), _) => MacAddress::Stable,
+            (Some("random"), _) => MacAddress::Random,
+            (Some(other), _) if !other.is_empty() => MacAddress::Other(other.to_string()),
+            (_, Some("1")) => MacAddress::Permanent,
+            (_, Some("2")) => MacAddress::Random,
+            _ => MacAddress::Default,
+        }
+    }
+
+    /// `wifi.cloned-mac-address` value, `None` to leave the key out
+    pub fn keyfile_value(&self) -> Option<&str> {
+        match self {
+            MacAddress::Default => None,
+            MacAddress::Permanent => Some("permanent"),
+            MacAddress::Stable => Some("stable"),
+            MacAddress::Random => Some("random"),
+            MacAddress::Other(value) => Some(value),
+        }
+    }
+}
+
+impl fmt::Display for MacAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MacAddress::Default => f.write_str("default"),
+            MacAddress::Permanent => f.write_str("device MAC"),
+            MacAddress::Stable => f.write_str("random per network"),
+            MacAddress::Random => f.write_str("random per connection"),
+            MacAddress::Other(value) => f.write_str(value),
+        }
+    }
+}
+
+/// `[802-1x]` settings of an enterprise profile. Empty strings mean "not set";
+/// the EAP password is kept in the profile's regular password field.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnterpriseSettings {
+    /// Outer EAP method: `peap`, `ttls` or `tls`
+    pub eap: String,
+    pub identity: String,
+    pub anonymous_identity: String,
+    /// Inner method for PEAP/TTLS, e.g. `mschapv2`
+    pub phase2_auth: String,
+    pub ca_cert: String,
+    pub client_cert: String,
+    pub private_key: String,
+    pub private_key_password: String,
+    pub domain_suffix_match: String,
+}
+
+impl EnterpriseSettings {
+    pub const EAP_METHODS: &'static [&'static str] = &["peap", "ttls", "tls"];
+    pub const PHASE2_METHODS: &'static [&'static str] = &["mschapv2", "gtc", "pap", "chap", "mschap", "md5"];
+
+    /// Check that the fields required by the EAP method are present
+    pub fn validate(&self) -> Result<(), String> {
+        if self.identity.trim().is_empty() {
+            return Err("Enterprise networks need an identity".to_string());
+        }
+        match self.eap.as_str() {
+            "tls" if self.client_cert.is_empty() || self.private_key.is_empty() => {
+                Err("EAP-TLS needs a client certificate and private key".to_string())
+            }
+            "tls" => Ok(()),
+            "peap" | "ttls" if self.phase2_auth.is_empty() => {
+                Err("PEAP/TTLS need a phase 2 method".to_string())
+            }
+            "peap" | "ttls" => Ok(()),
+            _ => Err("Select an EAP method".to_string()),
+        }
+    }
+}
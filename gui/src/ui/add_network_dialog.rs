@@ -1,11 +1,14 @@
 use iced::widget::{button, column, container, row, text, text_input, pick_list};
 use iced::{Element, Length};
+use crate::models::network::{EnterpriseSettings, Security};
+use crate::ui::enterprise_form::{self, EnterpriseMessage};
 
 #[derive(Debug, Clone)]
 pub enum AddNetworkMessage {
     SsidChanged(String),
     PasswordChanged(String),
-    SecurityChanged(String),
+    SecurityChanged(Security),
+    Enterprise(EnterpriseMessage),
     Submit,
     Cancel,
 }
@@ -14,7 +17,9 @@ pub enum AddNetworkMessage {
 pub struct AddNetworkState {
     pub ssid: String,
     pub password: String,
-    pub security: String,
+    pub security: Security,
+    pub enterprise: EnterpriseSettings,
+    pub error: Option<String>,
     pub visible: bool,
 }
 
@@ -23,7 +28,9 @@ impl AddNetworkState {
         Self {
             ssid: String::new(),
             password: String::new(),
-            security: "WPA/WPA2".to_string(),
+            security: Security::WpaPsk,
+            enterprise: enterprise_form::defaults(),
+            error: None,
             visible: false,
         }
     }
@@ -32,47 +39,74 @@ impl AddNetworkState {
         self.visible = true;
         self.ssid.clear();
         self.password.clear();
-        self.security = "WPA/WPA2".to_string();
+        self.security = Security::WpaPsk;
+        self.enterprise = enterprise_form::defaults();
+        self.error = None;
     }
     
     pub fn hide(&mut self) {
         self.visible = false;
     }
+    
+    /// Check the form before creating the profile
+    pub fn validate(&self) -> Result<(), String> {
+        if self.ssid.trim().is_empty() {
+            return Err("SSID must not be empty".to_string());
+        }
+        if self.security == Security::Enterprise {
+            self.enterprise.validate()?;
+        }
+        Ok(())
+    }
 }
 
-const SECURITY_OPTIONS: &[&str] = &["WPA/WPA2", "WPA3", "Open"];
-
 pub fn view<'a>(state: &'a AddNetworkState) -> Element<'a, AddNetworkMessage> {
     if !state.visible {
         return container(column![]).into();
     }
     
-    let content = column![
+    let password_hint = if state.security == Security::Enterprise {
+        "EAP password (leave empty to be asked)"
+    } else {
+        "Password (leave empty for Open)"
+    };
+    
+    let mut content = column![
         text("Add New Network").size(18),
         text("SSID:").size(14),
         text_input("Network name", &state.ssid)
             .on_input(AddNetworkMessage::SsidChanged)
             .padding(8),
         text("Password:").size(14),
-        text_input("Password (leave empty for Open)", &state.password)
+        text_input(password_hint, &state.password)
             .on_input(AddNetworkMessage::PasswordChanged)
             .padding(8)
             .secure(true),
         text("Security:").size(14),
         pick_list(
-            SECURITY_OPTIONS.to_vec(),
-            Some(state.security.as_str()),
-            |s| AddNetworkMessage::SecurityChanged(s.to_string()),
+            Security::SELECTABLE,
+            Some(state.security.clone()),
+            AddNetworkMessage::SecurityChanged,
         ),
-        row![
-            button("Cancel").on_press(AddNetworkMessage::Cancel),
-            button("Add Network").on_press(AddNetworkMessage::Submit),
-        ].spacing(10),
     ]
     .spacing(10)
     .padding(20)
     .width(Length::Fixed(350.0));
     
+    if state.security == Security::Enterprise {
+        content = content.push(enterprise_form::view(&state.enterprise).map(AddNetworkMessage::Enterprise));
+    }
+    if let Some(error) = &state.error {
+        content = content.push(text(error.as_str()).size(13));
+    }
+    
+    content = content.push(
+        row![
+            button("Cancel").on_press(AddNetworkMessage::Cancel),
+            button("Add Network").on_press(AddNetworkMessage::Submit),
+        ].spacing(10),
+    );
+    
     container(content)
         .padding(10)
         .into()
@@ -0,0 +1,58 @@
+use iced::widget::{button, checkbox, column, container, row, text};
+use iced::{Element, Length};
+
+#[derive(Debug, Clone)]
+pub enum DeleteMessage {
+    ForgetToggled(bool),
+    Confirm,
+    Cancel,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DeleteState {
+    /// Indices into the network list
+    pub targets: Vec<usize>,
+    pub ssids: Vec<String>,
+    pub forget: bool,
+    pub visible: bool,
+}
+
+impl DeleteState {
+    pub fn show(&mut self, targets: Vec<usize>, ssids: Vec<String>) {
+        self.targets = targets;
+        self.ssids = ssids;
+        self.forget = false;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.targets.clear();
+        self.ssids.clear();
+    }
+}
+
+pub fn view<'a>(state: &'a DeleteState) -> Element<'a, DeleteMessage> {
+    if !state.visible {
+        return container(column![]).into();
+    }
+
+    let content = column![
+        text(format!("Delete {} network(s)?", state.ssids.len())).size(18),
+        text(state.ssids.join(", ")).size(13),
+        text("Files are moved to the trash and can be restored.").size(12),
+        checkbox("Also forget from system (requires authentication)", state.forget)
+            .on_toggle(DeleteMessage::ForgetToggled),
+        row![
+            button("Cancel").on_press(DeleteMessage::Cancel),
+            button("Delete").on_press(DeleteMessage::Confirm),
+        ].spacing(10),
+    ]
+    .spacing(10)
+    .padding(20)
+    .width(Length::Fixed(350.0));
+
+    container(content)
+        .padding(10)
+        .into()
+}
@@ -0,0 +1,77 @@
+use iced::widget::{button, column, row, scrollable, text, Column};
+use iced::{Color, Element, Length};
+use crate::backend::diff::{Change, FileDiff, KeyDiff};
+
+#[derive(Debug, Clone)]
+pub enum DiffMessage {
+    Close,
+}
+
+const ADDED: Color = Color::from_rgb(0.45, 0.85, 0.5);
+const REMOVED: Color = Color::from_rgb(0.95, 0.4, 0.4);
+const CHANGED: Color = Color::from_rgb(0.95, 0.8, 0.35);
+const IGNORED: Color = Color::from_rgb(0.5, 0.5, 0.55);
+
+/// Local file (left) against the installed copy (right), key by key
+pub fn view(diff: &FileDiff) -> Element<'_, DiffMessage> {
+    let header = row![
+        text("Key").width(Length::FillPortion(2)),
+        text("Local").width(Length::FillPortion(3)),
+        text("System").width(Length::FillPortion(3)),
+    ]
+    .spacing(10)
+    .padding(5);
+
+    let mut body = Column::new().spacing(2);
+    for section in &diff.sections {
+        body = body.push(text(format!("[{}]", section.name)).size(14));
+        for key in &section.keys {
+            body = body.push(key_row(key));
+        }
+    }
+
+    column![
+        row![
+            text(format!("Diff: {}", diff.filename)).size(18).width(Length::Fill),
+            button("Back").on_press(DiffMessage::Close),
+        ]
+        .spacing(10),
+        text(diff.summary()).size(13),
+        header,
+        scrollable(body).height(Length::Fill),
+    ]
+    .spacing(8)
+    .into()
+}
+
+fn key_row(key: &KeyDiff) -> Element<'_, DiffMessage> {
+    let change = key.change();
+    let color = match change {
+        _ if key.ignored => Some(IGNORED),
+        Change::Same => None,
+        Change::OnlyLocal => Some(ADDED),
+        Change::OnlySystem => Some(REMOVED),
+        Change::Changed => Some(CHANGED),
+    };
+    let marker = match change {
+        Change::Same => ' ',
+        Change::OnlyLocal => '+',
+        Change::OnlySystem => '-',
+        Change::Changed => '~',
+    };
+
+    let value = |value: &Option<String>| match value {
+        Some(_) if key.is_secret() => "••••••••".to_string(),
+        Some(value) => value.clone(),
+        None => String::new(),
+    };
+
+    row![
+        text(format!("{} {}", marker, key.key)).size(13).color_maybe(color).width(Length::FillPortion(2)),
+        text(value(&key.local)).size(13).color_maybe(color).width(Length::FillPortion(3)),
+        text(value(&key.system)).size(13).color_maybe(color).width(Length::FillPortion(3)),
+    ]
+    .spacing(10)
+    .padding([2, 4])
+    .into()
+}
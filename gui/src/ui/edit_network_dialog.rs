@@ -0,0 +1,165 @@
+use iced::widget::{button, checkbox, column, container, row, text, text_input, pick_list};
+use iced::{Element, Length};
+use std::path::PathBuf;
+use crate::models::network::{EnterpriseSettings, NetworkSettings, Security};
+use crate::ui::enterprise_form::{self, EnterpriseMessage};
+
+#[derive(Debug, Clone)]
+pub enum EditNetworkMessage {
+    SsidChanged(String),
+    PasswordChanged(String),
+    SecurityChanged(Security),
+    Enterprise(EnterpriseMessage),
+    HiddenToggled(bool),
+    AutoconnectToggled(bool),
+    PriorityChanged(String),
+    Submit,
+    Cancel,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EditNetworkState {
+    pub path: PathBuf,
+    pub original: Option<NetworkSettings>,
+    pub ssid: String,
+    pub password: String,
+    pub security: Security,
+    pub enterprise: EnterpriseSettings,
+    pub hidden: bool,
+    pub autoconnect: bool,
+    pub priority: String,
+    pub error: Option<String>,
+    pub visible: bool,
+}
+
+impl EditNetworkState {
+    pub fn show(&mut self, path: PathBuf, settings: NetworkSettings) {
+        self.path = path;
+        self.ssid = settings.ssid.clone();
+        self.password = settings.password.clone();
+        self.security = settings.security.clone();
+        self.enterprise = if settings.security == Security::Enterprise {
+            settings.enterprise.clone()
+        } else {
+            enterprise_form::defaults()
+        };
+        self.hidden = settings.hidden;
+        self.autoconnect = settings.autoconnect;
+        self.priority = settings.priority.to_string();
+        self.original = Some(settings);
+        self.error = None;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.original = None;
+        self.password.clear();
+    }
+
+    /// Validate the form and build the updated settings
+    pub fn settings(&self) -> Result<NetworkSettings, String> {
+        if self.ssid.trim().is_empty() {
+            return Err("SSID must not be empty".to_string());
+        }
+        let priority = self.priority.trim().parse::<i32>()
+            .map_err(|_| "Priority must be a whole number".to_string())?;
+        // NetworkManager only accepts -999..999
+        if !(-999..=999).contains(&priority) {
+            return Err("Priority must be between -999 and 999".to_string());
+        }
+
+        // Settings the dialog does not edit are kept as loaded
+        let original = self.original.as_ref().ok_or("No network is being edited")?;
+
+        let enterprise = if self.security == Security::Enterprise {
+            // Profiles with an EAP method the form does not offer can still be
+            // renamed or reprioritised, as long as their [802-1x] is left alone
+            if original.security != Security::Enterprise || self.enterprise != original.enterprise {
+                self.enterprise.validate()?;
+            }
+            self.enterprise.clone()
+        } else {
+            EnterpriseSettings::default()
+        };
+
+        Ok(NetworkSettings {
+            ssid: self.ssid.clone(),
+            password: self.password.clone(),
+            security: self.security.clone(),
+            hidden: self.hidden,
+            autoconnect: self.autoconnect,
+            priority,
+            metered: original.metered,
+            mac_address: original.mac_address.clone(),
+            enterprise,
+        })
+    }
+}
+
+pub fn view<'a>(state: &'a EditNetworkState) -> Element<'a, EditNetworkMessage> {
+    if !state.visible {
+        return container(column![]).into();
+    }
+
+    let password_hint = if state.security == Security::Enterprise {
+        "EAP password (leave empty to be asked)"
+    } else {
+        "Password (leave empty for Open)"
+    };
+    
+    let mut content = column![
+        text("Edit Network").size(18),
+        text("SSID:").size(14),
+        text_input("Network name", &state.ssid)
+            .on_input(EditNetworkMessage::SsidChanged)
+            .padding(8),
+        text("Password:").size(14),
+        text_input(password_hint, &state.password)
+            .on_input(EditNetworkMessage::PasswordChanged)
+            .padding(8)
+            .secure(true),
+        text("Security:").size(14),
+        pick_list(
+            Security::SELECTABLE,
+            Some(state.security.clone()),
+            EditNetworkMessage::SecurityChanged,
+        ),
+    ]
+    .spacing(10)
+    .padding(20)
+    .width(Length::Fixed(350.0));
+    
+    if state.security == Security::Enterprise {
+        content = content.push(enterprise_form::view(&state.enterprise).map(EditNetworkMessage::Enterprise));
+    }
+    
+    content = content
+        .push(
+            row![
+                checkbox("Hidden", state.hidden).on_toggle(EditNetworkMessage::HiddenToggled),
+                checkbox("Autoconnect", state.autoconnect).on_toggle(EditNetworkMessage::AutoconnectToggled),
+            ].spacing(20),
+        )
+        .push(text("Autoconnect priority:").size(14))
+        .push(
+            text_input("0", &state.priority)
+                .on_input(EditNetworkMessage::PriorityChanged)
+                .padding(8),
+        );
+
+    if let Some(error) = &state.error {
+        content = content.push(text(error.as_str()).size(13));
+    }
+
+    content = content.push(
+        row![
+            button("Cancel").on_press(EditNetworkMessage::Cancel),
+            button("Save").on_press(EditNetworkMessage::Submit),
+        ].spacing(10),
+    );
+
+    container(content)
+        .padding(10)
+        .into()
+}
@@ -0,0 +1,101 @@
+use iced::widget::{column, pick_list, row, text, text_input};
+use iced::Element;
+use crate::models::network::EnterpriseSettings;
+
+/// 802.1X fields shared by the add and edit dialogs
+#[derive(Debug, Clone)]
+pub enum EnterpriseMessage {
+    Eap(&'static str),
+    Identity(String),
+    AnonymousIdentity(String),
+    Phase2(&'static str),
+    CaCert(String),
+    ClientCert(String),
+    PrivateKey(String),
+    PrivateKeyPassword(String),
+    DomainSuffixMatch(String),
+}
+
+pub fn update(settings: &mut EnterpriseSettings, message: EnterpriseMessage) {
+    match message {
+        EnterpriseMessage::Eap(eap) => settings.eap = eap.to_string(),
+        EnterpriseMessage::Identity(v) => settings.identity = v,
+        EnterpriseMessage::AnonymousIdentity(v) => settings.anonymous_identity = v,
+        EnterpriseMessage::Phase2(method) => settings.phase2_auth = method.to_string(),
+        EnterpriseMessage::CaCert(v) => settings.ca_cert = v,
+        EnterpriseMessage::ClientCert(v) => settings.client_cert = v,
+        EnterpriseMessage::PrivateKey(v) => settings.private_key = v,
+        EnterpriseMessage::PrivateKeyPassword(v) => settings.private_key_password = v,
+        EnterpriseMessage::DomainSuffixMatch(v) => settings.domain_suffix_match = v,
+    }
+}
+
+/// Defaults for a new enterprise profile (PEAP/MSCHAPv2)
+pub fn defaults() -> EnterpriseSettings {
+    EnterpriseSettings {
+        eap: "peap".to_string(),
+        phase2_auth: "mschapv2".to_string(),
+        ..Default::default()
+    }
+}
+
+pub fn view(settings: &EnterpriseSettings) -> Element<'_, EnterpriseMessage> {
+    let eap = EnterpriseSettings::EAP_METHODS.iter().copied().find(|m| *m == settings.eap);
+    let phase2 = EnterpriseSettings::PHASE2_METHODS.iter().copied().find(|m| *m == settings.phase2_auth);
+
+    let mut content = column![
+        row![
+            text("EAP:").size(14),
+            pick_list(EnterpriseSettings::EAP_METHODS, eap, EnterpriseMessage::Eap),
+        ]
+        .spacing(10),
+        text_input("Identity", &settings.identity)
+            .on_input(EnterpriseMessage::Identity)
+            .padding(8),
+        text_input("Anonymous identity (optional)", &settings.anonymous_identity)
+            .on_input(EnterpriseMessage::AnonymousIdentity)
+            .padding(8),
+    ]
+    .spacing(10);
+
+    if settings.eap == "tls" {
+        content = content
+            .push(
+                text_input("Client certificate file", &settings.client_cert)
+                    .on_input(EnterpriseMessage::ClientCert)
+                    .padding(8),
+            )
+            .push(
+                text_input("Private key file", &settings.private_key)
+                    .on_input(EnterpriseMessage::PrivateKey)
+                    .padding(8),
+            )
+            .push(
+                text_input("Private key password", &settings.private_key_password)
+                    .on_input(EnterpriseMessage::PrivateKeyPassword)
+                    .padding(8)
+                    .secure(true),
+            );
+    } else {
+        content = content.push(
+            row![
+                text("Phase 2:").size(14),
+                pick_list(EnterpriseSettings::PHASE2_METHODS, phase2, EnterpriseMessage::Phase2),
+            ]
+            .spacing(10),
+        );
+    }
+
+    content
+        .push(
+            text_input("CA certificate file (optional)", &settings.ca_cert)
+                .on_input(EnterpriseMessage::CaCert)
+                .padding(8),
+        )
+        .push(
+            text_input("Domain suffix match (optional)", &settings.domain_suffix_match)
+                .on_input(EnterpriseMessage::DomainSuffixMatch)
+                .padding(8),
+        )
+        .into()
+}
@@ -0,0 +1,73 @@
+use iced::widget::{button, column, container, row, scrollable, text, Column};
+use iced::{Element, Length};
+use crate::backend::android_export::MergeResult;
+
+#[derive(Debug, Clone)]
+pub enum ExportPreviewMessage {
+    Push,
+    Cancel,
+}
+
+/// The merged WifiConfigStore.xml, reviewed before push-android
+#[derive(Debug, Clone, Default)]
+pub struct ExportPreviewState {
+    pub result: MergeResult,
+    pub visible: bool,
+}
+
+impl ExportPreviewState {
+    pub fn show(&mut self, result: MergeResult) {
+        self.result = result;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.result = MergeResult::default();
+    }
+}
+
+pub fn view(state: &ExportPreviewState) -> Element<'_, ExportPreviewMessage> {
+    if !state.visible {
+        return container(column![]).into();
+    }
+    let result = &state.result;
+
+    let mut changes = Column::new().spacing(2);
+    for ssid in &result.added {
+        changes = changes.push(text(format!("+ {} (added)", ssid)).size(13));
+    }
+    for (ssid, what) in &result.updated {
+        changes = changes.push(text(format!("~ {} ({} updated)", ssid, what)).size(13));
+    }
+    for (ssid, reason) in &result.skipped {
+        changes = changes.push(text(format!("✗ {} (skipped: {})", ssid, reason)).size(13));
+    }
+
+    let summary = if result.has_changes() {
+        format!(
+            "{} added, {} updated, {} skipped; the device's other networks are kept",
+            result.added.len(),
+            result.updated.len(),
+            result.skipped.len()
+        )
+    } else {
+        "The store already has every local network".to_string()
+    };
+    let push_label = if result.has_changes() { "Write and push" } else { "Push" };
+
+    column![
+        text("Android Export Preview").size(18),
+        text(summary).size(12),
+        changes,
+        text("Merged WifiConfigStore.xml").size(12),
+        scrollable(text(result.xml.as_str()).size(11).font(iced::Font::MONOSPACE)).height(Length::Fill),
+        row![
+            button("Cancel").on_press(ExportPreviewMessage::Cancel),
+            button(push_label).on_press(ExportPreviewMessage::Push),
+        ]
+        .spacing(10),
+    ]
+    .spacing(8)
+    .into()
+}
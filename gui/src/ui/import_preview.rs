@@ -0,0 +1,102 @@
+use iced::widget::{button, column, container, row, scrollable, text, Column};
+use iced::{Element, Length};
+use crate::backend::android_import;
+use crate::backend::android_xml::{AndroidNetwork, Credentials};
+
+#[derive(Debug, Clone)]
+pub enum ImportPreviewMessage {
+    Confirm,
+    Cancel,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImportPreviewState {
+    pub networks: Vec<AndroidNetwork>,
+    pub visible: bool,
+}
+
+impl ImportPreviewState {
+    pub fn show(&mut self, networks: Vec<AndroidNetwork>) {
+        self.networks = networks;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.networks.clear();
+    }
+}
+
+pub fn view<'a>(state: &'a ImportPreviewState) -> Element<'a, ImportPreviewMessage> {
+    if !state.visible {
+        return container(column![]).into();
+    }
+
+    let header = row![
+        text("SSID").width(Length::FillPortion(3)),
+        text("Security").width(Length::FillPortion(2)),
+        text("Priority").width(Length::FillPortion(1)),
+        text("Hidden").width(Length::FillPortion(1)),
+        text("Metered").width(Length::FillPortion(1)),
+    ]
+    .spacing(10)
+    .padding(5);
+
+    let rows: Vec<Element<'a, ImportPreviewMessage>> = state
+        .networks
+        .iter()
+        .map(|network| {
+            let mac = network
+                .randomized_mac
+                .as_deref()
+                .map(|m| format!("  MAC {} ({})", m, network.mac_address))
+                .unwrap_or_else(|| format!("  MAC: {}", network.mac_address));
+            let issue = match android_import::import_issue(network) {
+                Some(issue) => format!("  not imported: {}", issue),
+                None => android_import::import_notes(network)
+                    .iter()
+                    .map(|note| format!("\n  ! {}", note))
+                    .collect(),
+            };
+
+            column![
+                row![
+                    text(network.ssid.as_str()).width(Length::FillPortion(3)).size(13),
+                    text(network.security.to_string()).width(Length::FillPortion(2)).size(13),
+                    text(network.priority.to_string()).width(Length::FillPortion(1)).size(13),
+                    text(if network.hidden { "yes" } else { "no" }).width(Length::FillPortion(1)).size(13),
+                    text(network.metered.to_string()).width(Length::FillPortion(1)).size(13),
+                ]
+                .spacing(10),
+                text(format!("{}{}{}", network.config_key, mac, issue)).size(10),
+            ]
+            .padding(4)
+            .into()
+        })
+        .collect();
+
+    column![
+        text("Android Import Preview").size(18),
+        text(source_summary(&state.networks)).size(12),
+        header,
+        scrollable(Column::with_children(rows).spacing(2)).height(Length::Fill),
+        row![
+            button("Cancel").on_press(ImportPreviewMessage::Cancel),
+            button("Import").on_press(ImportPreviewMessage::Confirm),
+        ]
+        .spacing(10),
+    ]
+    .spacing(8)
+    .into()
+}
+
+fn source_summary(networks: &[AndroidNetwork]) -> String {
+    if !networks.is_empty() && networks.iter().all(|n| n.credentials == Credentials::NotPulled) {
+        format!(
+            "{} networks listed by the device without root; passwords are only in WifiConfigStore.xml",
+            networks.len()
+        )
+    } else {
+        format!("{} networks found in WifiConfigStore.xml", networks.len())
+    }
+}
@@ -2,3 +2,16 @@ pub mod network_list;
 pub mod toolbar;
 pub mod output_panel;
 pub mod add_network_dialog;
+pub mod edit_network_dialog;
+pub mod enterprise_form;
+pub mod import_preview;
+pub mod export_preview;
+pub mod delete_dialog;
+pub mod prompt_dialog;
+pub mod password_dialog;
+pub mod trash_view;
+pub mod diff_view;
+pub mod plan_view;
+pub mod settings_view;
+pub mod workspace_bar;
+pub mod sync_status;
@@ -1,5 +1,7 @@
-use iced::widget::{button, column, container, row, scrollable, text, text_input, Column};
+use iced::widget::{button, checkbox, column, container, row, scrollable, text, text_input, Column};
 use iced::{Element, Length};
+use std::collections::HashMap;
+use crate::backend::script::Action;
 use crate::models::network::Network;
 
 #[derive(Debug, Clone)]
@@ -7,12 +9,21 @@ pub enum NetworkListMessage {
     SearchChanged(String),
     AddNetwork,
     TogglePassword(usize),
+    EditNetwork(usize),
+    /// Compare with the installed copy
+    ShowDiff(usize),
+    ToggleSelected(usize, bool),
+    DeleteSelected,
+    DeleteNetwork(usize),
+    ShowTrash,
 }
 
 pub fn view<'a>(
     networks: &'a [Network], 
     search_query: &'a str,
     show_passwords: &'a [bool],
+    selected: &'a [bool],
+    actions: &'a HashMap<String, Action>,
 ) -> Element<'a, NetworkListMessage> {
     // Search box
     let search_box = text_input("Search networks...", search_query)
@@ -25,10 +36,23 @@ pub fn view<'a>(
         .on_press(NetworkListMessage::AddNetwork)
         .padding(8);
     
+    let selected_count = selected.iter().filter(|s| **s).count();
+    let delete_btn = button(text(format!("Delete Selected ({})", selected_count)).size(12)).padding(4);
+    let delete_btn = if selected_count > 0 {
+        delete_btn.on_press(NetworkListMessage::DeleteSelected)
+    } else {
+        delete_btn
+    };
+    let trash_btn = button(text("Trash").size(12))
+        .on_press(NetworkListMessage::ShowTrash)
+        .padding(4);
+    
     let header = row![
+        text("").width(Length::Fixed(20.0)),
         text("SSID").width(Length::FillPortion(3)),
         text("Security").width(Length::FillPortion(2)),
         text("Password").width(Length::FillPortion(3)),
+        text("").width(Length::Shrink),
     ]
     .spacing(10)
     .padding(5);
@@ -62,16 +86,35 @@ pub fn view<'a>(
             
             // Clone values to avoid lifetime issues
             let ssid = network.ssid.clone();
-            let security = network.security.clone();
+            let security = network.security_display();
             let idx_copy = *idx;
+            let is_selected = selected.get(*idx).copied().unwrap_or(false);
+            
+            // What the last command did to this network
+            let mut ssid_cell = column![text(ssid).size(13)];
+            if let Some(action) = actions.get(&network.ssid) {
+                ssid_cell = ssid_cell.push(text(action.to_string()).size(10));
+            }
             
             row![
-                text(ssid).width(Length::FillPortion(3)).size(13),
+                checkbox("", is_selected)
+                    .on_toggle(move |checked| NetworkListMessage::ToggleSelected(idx_copy, checked))
+                    .width(Length::Fixed(20.0)),
+                ssid_cell.width(Length::FillPortion(3)),
                 text(security).width(Length::FillPortion(2)).size(13),
                 button(text(pass_text).size(12))
                     .on_press(NetworkListMessage::TogglePassword(idx_copy))
                     .padding(2)
                     .width(Length::FillPortion(3)),
+                button(text("Diff").size(12))
+                    .on_press(NetworkListMessage::ShowDiff(idx_copy))
+                    .padding(2),
+                button(text("Edit").size(12))
+                    .on_press(NetworkListMessage::EditNetwork(idx_copy))
+                    .padding(2),
+                button(text("Delete").size(12))
+                    .on_press(NetworkListMessage::DeleteNetwork(idx_copy))
+                    .padding(2),
             ]
             .spacing(10)
             .padding(4)
@@ -84,7 +127,7 @@ pub fn view<'a>(
     
     column![
         row![search_box, add_btn].spacing(10),
-        count_text,
+        row![count_text.width(Length::Fill), delete_btn, trash_btn].spacing(10),
         header,
         container(text("─".repeat(60))).padding(2),
         scrollable(list).height(Length::Fill),
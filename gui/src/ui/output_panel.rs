@@ -1,5 +1,6 @@
-use iced::widget::{button, column, container, row, scrollable, text};
+use iced::widget::{button, column, container, row, scrollable, text, Column};
 use iced::{Border, Color, Element, Length, Theme};
+use crate::backend::script::{Level, OutputStream};
 
 #[derive(Debug, Clone)]
 pub enum OutputMessage {
@@ -7,21 +8,55 @@ pub enum OutputMessage {
     Clear,
 }
 
-pub fn view<'a>(output: &'a str) -> Element<'a, OutputMessage> {
+/// A line in the terminal, tagged with the pipe it came from and,
+/// for script events, their level
+#[derive(Debug, Clone)]
+pub struct OutputLine {
+    pub stream: OutputStream,
+    pub level: Option<Level>,
+    pub text: String,
+}
+
+/// Plain text of the terminal, for copying
+pub fn to_text(lines: &[OutputLine]) -> String {
+    lines.iter().map(|l| format!("{}\n", l.text)).collect()
+}
+
+pub fn view<'a>(lines: &'a [OutputLine]) -> Element<'a, OutputMessage> {
     let buttons = row![
         button("📋 Copy").on_press(OutputMessage::Copy),
         button("🗑 Clear").on_press(OutputMessage::Clear),
     ]
     .spacing(10);
     
-    // Terminal-like styling
-    let terminal_content = container(
-        scrollable(
-            text(if output.is_empty() { "Terminal output will appear here..." } else { output })
+    let content: Element<'a, OutputMessage> = if lines.is_empty() {
+        text("Terminal output will appear here...")
+            .size(13)
+            .font(iced::Font::MONOSPACE)
+            .into()
+    } else {
+        Column::with_children(lines.iter().map(|line| {
+            let color = match (line.level, line.stream) {
+                (Some(Level::Error), _) => Some(Color::from_rgb(0.95, 0.4, 0.4)),
+                (Some(Level::Warn), _) => Some(Color::from_rgb(0.95, 0.8, 0.35)),
+                (Some(_), _) | (None, OutputStream::Stdout) => None,
+                (None, OutputStream::Stderr) => Some(Color::from_rgb(0.95, 0.55, 0.5)),
+            };
+            text(line.text.as_str())
                 .size(13)
                 .font(iced::Font::MONOSPACE)
-        )
-        .height(Length::Fill)
+                .color_maybe(color)
+                .into()
+        }))
+        .into()
+    };
+    
+    // Terminal-like styling; stays pinned to the newest line while output streams in
+    let terminal_content = container(
+        scrollable(content)
+            .anchor_bottom()
+            .width(Length::Fill)
+            .height(Length::Fill)
     )
     .width(Length::Fill)
     .height(Length::Fill)
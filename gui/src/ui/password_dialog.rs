@@ -0,0 +1,58 @@
+use iced::widget::{button, column, container, row, text, text_input};
+use iced::{Element, Length, Task};
+use crate::backend::askpass::AskpassRequest;
+
+const PASSWORD_ID: &str = "askpass-password";
+
+#[derive(Debug, Clone)]
+pub enum PasswordMessage {
+    Changed(String),
+    Submit,
+    Cancel,
+}
+
+/// sudo's password prompt, answered from the GUI
+#[derive(Debug, Clone)]
+pub struct PasswordState {
+    pub request: AskpassRequest,
+    pub password: String,
+}
+
+impl PasswordState {
+    pub fn new(request: AskpassRequest) -> Self {
+        PasswordState { request, password: String::new() }
+    }
+
+    /// Put the cursor in the password field
+    pub fn focus<Message: 'static>() -> Task<Message> {
+        text_input::focus(text_input::Id::new(PASSWORD_ID))
+    }
+}
+
+pub fn view(state: &PasswordState) -> Element<'_, PasswordMessage> {
+    let prompt = if state.request.prompt.is_empty() { "Password:" } else { state.request.prompt.as_str() };
+
+    let content = column![
+        text("Authentication required").size(18),
+        text("sudo needs your password to change the system connections").size(13),
+        text(prompt).size(12),
+        text_input("Password", &state.password)
+            .id(text_input::Id::new(PASSWORD_ID))
+            .secure(true)
+            .on_input(PasswordMessage::Changed)
+            .on_submit(PasswordMessage::Submit)
+            .padding(6),
+        row![
+            button("Cancel").on_press(PasswordMessage::Cancel),
+            button("OK").on_press(PasswordMessage::Submit),
+        ]
+        .spacing(10),
+    ]
+    .spacing(10)
+    .padding(20)
+    .width(Length::Fixed(420.0));
+
+    container(content)
+        .style(container::bordered_box)
+        .into()
+}
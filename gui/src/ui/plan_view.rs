@@ -0,0 +1,203 @@
+use iced::widget::{button, checkbox, column, row, scrollable, text, Column};
+use iced::{Color, Element, Length};
+use crate::backend::plan::{Direction, PlanItem};
+use crate::backend::script::Action;
+use crate::ui::diff_view;
+
+#[derive(Debug, Clone)]
+pub enum PlanMessage {
+    Toggled(usize, bool),
+    SelectAll(bool),
+    ShowDiff(usize),
+    HideDiff,
+    Apply,
+    Close,
+}
+
+/// Files install or backup would copy, and which of them to apply
+#[derive(Debug, Clone, Default)]
+pub struct PlanState {
+    pub visible: bool,
+    pub direction: Direction,
+    pub items: Vec<PlanItem>,
+    pub selected: Vec<bool>,
+    /// Item whose diff is shown instead of the checklist
+    pub diff: Option<usize>,
+    /// The selected items are being applied
+    pub applying: bool,
+    /// The files that were copied, once applying finished
+    pub applied: Option<Vec<(String, Action)>>,
+    pub error: Option<String>,
+}
+
+impl PlanState {
+    pub fn show(&mut self, direction: Direction, items: Vec<PlanItem>) {
+        self.direction = direction;
+        self.selected = vec![true; items.len()];
+        self.items = items;
+        self.diff = None;
+        self.applying = false;
+        self.applied = None;
+        self.error = None;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn update(&mut self, message: PlanMessage) {
+        match message {
+            PlanMessage::Toggled(idx, on) => {
+                if let Some(selected) = self.selected.get_mut(idx) {
+                    *selected = on;
+                }
+            }
+            PlanMessage::SelectAll(on) => self.selected.fill(on),
+            PlanMessage::ShowDiff(idx) => self.diff = Some(idx),
+            PlanMessage::HideDiff => self.diff = None,
+            PlanMessage::Apply | PlanMessage::Close => {}
+        }
+    }
+
+    /// Filenames of the selected items, passed to install/backup
+    pub fn selected_files(&self) -> Vec<String> {
+        self.items
+            .iter()
+            .zip(&self.selected)
+            .filter(|(_, selected)| **selected)
+            .map(|(item, _)| item.filename.clone())
+            .collect()
+    }
+
+    pub fn selected_items(&self) -> Vec<PlanItem> {
+        self.items
+            .iter()
+            .zip(&self.selected)
+            .filter(|(_, selected)| **selected)
+            .map(|(item, _)| item.clone())
+            .collect()
+    }
+
+    /// Applying ended with the copied files, or how it failed
+    pub fn finish(&mut self, result: Result<Vec<(String, Action)>, String>) {
+        self.applying = false;
+        match result {
+            Ok(applied) => self.applied = Some(applied),
+            Err(error) => {
+                self.applied = Some(Vec::new());
+                self.error = Some(error);
+            }
+        }
+    }
+}
+
+pub fn view(state: &PlanState) -> Element<'_, PlanMessage> {
+    let title = match state.direction {
+        Direction::Install => "Install plan",
+        Direction::Backup => "Backup plan",
+    };
+
+    if let Some(item) = state.diff.and_then(|idx| state.items.get(idx)) {
+        return diff_view::view(&item.diff).map(|_| PlanMessage::HideDiff);
+    }
+
+    let body: Element<'_, PlanMessage> = if let Some(applied) = &state.applied {
+        result(state, applied)
+    } else if state.items.is_empty() {
+        text(match state.direction {
+            Direction::Install => "Nothing to install: the system has the same files",
+            Direction::Backup => "Nothing to back up: the local directory has the same files",
+        })
+        .size(13)
+        .into()
+    } else {
+        checklist(state)
+    };
+
+    let close_label = if state.applied.is_some() { "Close" } else { "Cancel" };
+    column![
+        row![
+            text(title).size(18).width(Length::Fill),
+            button(close_label).on_press_maybe((!state.applying).then_some(PlanMessage::Close)),
+        ]
+        .spacing(10),
+        body,
+    ]
+    .spacing(8)
+    .into()
+}
+
+fn checklist(state: &PlanState) -> Element<'_, PlanMessage> {
+    let rows: Vec<Element<'_, PlanMessage>> = state
+        .items
+        .iter()
+        .zip(&state.selected)
+        .enumerate()
+        .map(|(idx, (item, selected))| {
+            row![
+                checkbox(item.filename.as_str(), *selected)
+                    .on_toggle_maybe((!state.applying).then_some(move |on| PlanMessage::Toggled(idx, on)))
+                    .size(16)
+                    .width(Length::FillPortion(3)),
+                text(item.action.to_string()).size(13).width(Length::FillPortion(1)),
+                text(item.diff.summary()).size(11).width(Length::FillPortion(3)),
+                button(text("Diff").size(12))
+                    .on_press(PlanMessage::ShowDiff(idx))
+                    .padding(2),
+            ]
+            .spacing(10)
+            .padding(4)
+            .into()
+        })
+        .collect();
+
+    let count = state.selected.iter().filter(|s| **s).count();
+    let all = count == state.items.len();
+    let apply_label = match state.direction {
+        Direction::Install => format!("Install {} selected", count),
+        Direction::Backup => format!("Back up {} selected", count),
+    };
+    let apply: Element<'_, PlanMessage> = if state.applying {
+        text("Applying...").size(13).into()
+    } else {
+        button(text(apply_label))
+            .on_press_maybe((count > 0).then_some(PlanMessage::Apply))
+            .into()
+    };
+
+    column![
+        checkbox("Select all", all)
+            .on_toggle_maybe((!state.applying).then_some(PlanMessage::SelectAll))
+            .size(16),
+        scrollable(Column::with_children(rows).spacing(2)).height(Length::Fill),
+        apply,
+    ]
+    .spacing(8)
+    .into()
+}
+
+/// Selected files and whether the command reported copying them
+fn result<'a>(state: &'a PlanState, applied: &'a [(String, Action)]) -> Element<'a, PlanMessage> {
+    let selected = state.selected_files();
+    let total = selected.len();
+    let done = selected.iter().filter(|f| applied.iter().any(|(file, _)| file == *f)).count();
+
+    let mut rows = Column::new().spacing(2);
+    for filename in selected {
+        let line = match applied.iter().find(|(file, _)| *file == filename) {
+            Some((_, action)) => text(format!("✓ {} ({})", filename, action)).size(13),
+            None => text(format!("✗ {} (not applied)", filename))
+                .size(13)
+                .color(Color::from_rgb(0.95, 0.4, 0.4)),
+        };
+        rows = rows.push(line);
+    }
+
+    let mut body = column![text(format!("Applied {} of {} selected", done, total)).size(14)]
+        .spacing(8);
+    if let Some(error) = &state.error {
+        body = body.push(text(error.as_str()).size(13).color(Color::from_rgb(0.95, 0.4, 0.4)));
+    }
+    body.push(scrollable(rows).height(Length::Fill)).into()
+}
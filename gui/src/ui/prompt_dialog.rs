@@ -0,0 +1,49 @@
+use iced::widget::{button, center, column, container, opaque, row, stack, text};
+use iced::{Color, Element, Length};
+use crate::backend::script::Prompt;
+
+#[derive(Debug, Clone)]
+pub enum PromptMessage {
+    Yes,
+    No,
+}
+
+pub fn view(prompt: &Prompt) -> Element<'_, PromptMessage> {
+    let mut content = column![
+        text("Confirm").size(18),
+        text(prompt.question.as_str()).size(14),
+    ]
+    .spacing(10)
+    .padding(20)
+    .width(Length::Fixed(420.0));
+
+    if !prompt.detail.is_empty() {
+        content = content.push(
+            text(format!("Target: {}", prompt.detail))
+                .size(12)
+                .font(iced::Font::MONOSPACE),
+        );
+    }
+
+    content = content.push(
+        row![
+            button("No").on_press(PromptMessage::No),
+            button("Yes").on_press(PromptMessage::Yes),
+        ]
+        .spacing(10),
+    );
+
+    container(content)
+        .style(container::bordered_box)
+        .into()
+}
+
+/// Show `dialog` centered over a dimmed `base`, blocking input to it
+pub fn modal<'a, Message: 'a>(base: Element<'a, Message>, dialog: Element<'a, Message>) -> Element<'a, Message> {
+    let backdrop = center(opaque(dialog)).style(|_theme| container::Style {
+        background: Some(Color { a: 0.7, ..Color::BLACK }.into()),
+        ..Default::default()
+    });
+
+    stack![base, opaque(backdrop)].into()
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use iced::widget::{button, checkbox, column, pick_list, row, scrollable, text, text_input, Column};
+use iced::{Element, Length};
+use crate::backend::config::{self, SETTINGS};
+
+#[derive(Debug, Clone)]
+pub enum SettingsMessage {
+    Changed(usize, String),
+    Toggled(usize, bool),
+    Save,
+    Close,
+}
+
+/// Values of `config::SETTINGS`, in the same order
+#[derive(Debug, Clone, Default)]
+pub struct SettingsState {
+    pub visible: bool,
+    pub values: Vec<String>,
+    original: Vec<String>,
+    /// Drop-down values of settings with `choices`, empty for the others
+    options: Vec<Vec<String>>,
+    pub error: Option<String>,
+}
+
+impl SettingsState {
+    pub fn show(&mut self, vars: &HashMap<String, String>) {
+        self.values = SETTINGS
+            .iter()
+            .map(|s| vars.get(s.name).cloned().unwrap_or_default())
+            .collect();
+        self.original = self.values.clone();
+        self.options = SETTINGS
+            .iter()
+            .zip(&self.values)
+            .map(|(setting, value)| {
+                let mut options = setting.choices.map(|choices| choices()).unwrap_or_default();
+                // Keep a configured value that is not offered, e.g. a tool that was uninstalled
+                if setting.choices.is_some() && !value.is_empty() && !options.contains(value) {
+                    options.push(value.clone());
+                }
+                options
+            })
+            .collect();
+        self.error = None;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.error = None;
+    }
+
+    pub fn update(&mut self, message: SettingsMessage) {
+        match message {
+            SettingsMessage::Changed(idx, value) => {
+                if let Some(v) = self.values.get_mut(idx) {
+                    *v = value;
+                }
+            }
+            SettingsMessage::Toggled(idx, on) => {
+                if let Some(v) = self.values.get_mut(idx) {
+                    *v = on.to_string();
+                }
+            }
+            SettingsMessage::Save | SettingsMessage::Close => {}
+        }
+    }
+
+    /// Settings that differ from what was loaded
+    pub fn changes(&self) -> Vec<(&'static str, String)> {
+        SETTINGS
+            .iter()
+            .zip(self.values.iter().zip(&self.original))
+            .filter(|(_, (value, original))| value != original)
+            .map(|(setting, (value, _))| (setting.name, value.trim().to_string()))
+            .collect()
+    }
+}
+
+pub fn view(state: &SettingsState) -> Element<'_, SettingsMessage> {
+    let fields: Vec<Element<'_, SettingsMessage>> = SETTINGS
+        .iter()
+        .zip(&state.values)
+        .enumerate()
+        .map(|(idx, (setting, value))| {
+            if setting.choices.is_some() {
+                let options = state.options.get(idx).map(Vec::as_slice).unwrap_or_default();
+                column![
+                    text(setting.label).size(12),
+                    pick_list(options, (!value.is_empty()).then(|| value.clone()), move |v| {
+                        SettingsMessage::Changed(idx, v)
+                    })
+                    .placeholder(setting.name)
+                    .padding(6)
+                    .text_size(13),
+                ]
+                .spacing(2)
+                .into()
+            } else if setting.flag {
+                checkbox(setting.label, value == "true")
+                    .on_toggle(move |on| SettingsMessage::Toggled(idx, on))
+                    .size(16)
+                    .into()
+            } else {
+                column![
+                    text(setting.label).size(12),
+                    text_input(setting.name, value)
+                        .on_input(move |v| SettingsMessage::Changed(idx, v))
+                        .padding(6)
+                        .size(13),
+                ]
+                .spacing(2)
+                .into()
+            }
+        })
+        .collect();
+
+    let target = config::user_config_path()
+        .map(|p| format!("Saved to {}", p.display()))
+        .unwrap_or_else(|| "HOME is not set, settings cannot be saved".to_string());
+
+    let mut content = column![
+        row![
+            text("Settings").size(18).width(Length::Fill),
+            button("Back").on_press(SettingsMessage::Close),
+        ]
+        .spacing(10),
+        text(target).size(11),
+        scrollable(Column::with_children(fields).spacing(8)).height(Length::Fill),
+    ]
+    .spacing(8);
+
+    if let Some(error) = &state.error {
+        content = content.push(text(error.as_str()).size(13));
+    }
+
+    let has_changes = !state.changes().is_empty();
+    content
+        .push(button("Save").on_press_maybe(has_changes.then_some(SettingsMessage::Save)))
+        .into()
+}
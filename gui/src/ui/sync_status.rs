@@ -0,0 +1,57 @@
+use iced::widget::{button, column, container, opaque, row, scrollable, stack, text, Column};
+use iced::alignment::Horizontal;
+use iced::{Element, Length};
+use crate::backend::sync::SyncStatus;
+
+#[derive(Debug, Clone)]
+pub enum SyncStatusMessage {
+    Toggle,
+    Close,
+}
+
+/// Lists what differs between the report and the connection files
+pub fn details(status: &SyncStatus) -> Element<'_, SyncStatusMessage> {
+    let mut lines = Column::new().spacing(4);
+
+    for ssid in &status.only_in_report {
+        lines = lines.push(text(format!("{}: only in report", ssid)).size(12));
+    }
+    for ssid in &status.only_in_files {
+        lines = lines.push(text(format!("{}: only in connection files", ssid)).size(12));
+    }
+    for mismatch in &status.mismatched {
+        for field in &mismatch.fields {
+            lines = lines.push(text(format!("{}: {}", mismatch.ssid, field)).size(12));
+        }
+    }
+    if status.is_synced() {
+        lines = lines.push(text("Report and connection files match").size(12));
+    }
+
+    container(
+        column![
+            row![
+                text("Sync status").size(16).width(Length::Fill),
+                button(text("Close").size(12)).on_press(SyncStatusMessage::Close).padding(4),
+            ]
+            .spacing(10),
+            scrollable(lines).height(Length::Shrink),
+        ]
+        .spacing(8)
+        .padding(12)
+        .width(Length::Fixed(420.0)),
+    )
+    .max_height(360.0)
+    .style(container::bordered_box)
+    .into()
+}
+
+/// Show `content` over the top-left of `base`, below the status bar
+pub fn popover<'a, Message: 'a>(base: Element<'a, Message>, content: Element<'a, Message>) -> Element<'a, Message> {
+    let positioned = container(opaque(content))
+        .width(Length::Fill)
+        .align_x(Horizontal::Left)
+        .padding(iced::Padding { top: 44.0, left: 10.0, ..Default::default() });
+
+    stack![base, positioned].into()
+}
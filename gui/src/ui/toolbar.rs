@@ -9,9 +9,11 @@ pub enum ToolbarMessage {
     Sync,
     PullAdb,
     PushAdb,
+    Cancel,
+    Settings,
 }
 
-pub fn view<'a>(is_loading: bool) -> Element<'a, ToolbarMessage> {
+pub fn view<'a>(is_loading: bool, can_cancel: bool) -> Element<'a, ToolbarMessage> {
     // Multi-line button for Sync
     let sync_button = button(
         column![
@@ -33,6 +35,8 @@ pub fn view<'a>(is_loading: bool) -> Element<'a, ToolbarMessage> {
     let backup_btn = if is_loading { button("Backup") } else { button("Backup").on_press(ToolbarMessage::Backup) };
     let pull_btn = if is_loading { button("Pull ADB") } else { button("Pull ADB").on_press(ToolbarMessage::PullAdb) };
     let push_btn = if is_loading { button("Push ADB") } else { button("Push ADB").on_press(ToolbarMessage::PushAdb) };
+    let cancel_btn = button("Cancel").on_press_maybe(can_cancel.then_some(ToolbarMessage::Cancel));
+    let settings_btn = button("Settings").on_press_maybe((!is_loading).then_some(ToolbarMessage::Settings));
 
     row![
         import_btn,
@@ -41,6 +45,8 @@ pub fn view<'a>(is_loading: bool) -> Element<'a, ToolbarMessage> {
         sync_button,
         pull_btn,
         push_btn,
+        cancel_btn,
+        settings_btn,
     ]
     .spacing(10)
     .padding(10)
@@ -0,0 +1,61 @@
+use iced::widget::{button, column, row, scrollable, text, Column};
+use iced::{Element, Length};
+use crate::backend::trash::TrashEntry;
+
+#[derive(Debug, Clone)]
+pub enum TrashMessage {
+    Restore(usize),
+    Close,
+}
+
+pub fn view<'a>(entries: &'a [TrashEntry]) -> Element<'a, TrashMessage> {
+    let header = row![
+        text("SSID").width(Length::FillPortion(3)),
+        text("Deleted").width(Length::FillPortion(2)),
+        text("").width(Length::FillPortion(1)),
+    ]
+    .spacing(10)
+    .padding(5);
+
+    let rows: Vec<Element<'a, TrashMessage>> = entries
+        .iter()
+        .enumerate()
+        .map(|(idx, entry)| {
+            let deleted = if entry.forgotten {
+                format!("{} (forgotten)", entry.deleted_display())
+            } else {
+                entry.deleted_display()
+            };
+
+            row![
+                text(entry.ssid.as_str()).width(Length::FillPortion(3)).size(13),
+                text(deleted).width(Length::FillPortion(2)).size(13),
+                button(text("Restore").size(12))
+                    .on_press(TrashMessage::Restore(idx))
+                    .padding(2)
+                    .width(Length::FillPortion(1)),
+            ]
+            .spacing(10)
+            .padding(4)
+            .into()
+        })
+        .collect();
+
+    let body: Element<'a, TrashMessage> = if entries.is_empty() {
+        text("Trash is empty").size(13).into()
+    } else {
+        scrollable(Column::with_children(rows).spacing(2)).height(Length::Fill).into()
+    };
+
+    column![
+        row![
+            text("Trash").size(18).width(Length::Fill),
+            button("Back").on_press(TrashMessage::Close),
+        ]
+        .spacing(10),
+        header,
+        body,
+    ]
+    .spacing(8)
+    .into()
+}
@@ -0,0 +1,82 @@
+use iced::widget::{button, pick_list, row, text, text_input};
+use iced::{Alignment, Element, Length};
+use crate::backend::workspace::{self, Workspaces};
+
+#[derive(Debug, Clone)]
+pub enum WorkspaceMessage {
+    Selected(String),
+    ShowAdd,
+    NameChanged(String),
+    DirChanged(String),
+    Add,
+    CancelAdd,
+    RemoveActive,
+}
+
+/// The "new workspace" form shown in the status bar
+#[derive(Debug, Clone, Default)]
+pub struct WorkspaceForm {
+    pub visible: bool,
+    pub name: String,
+    pub dir: String,
+    pub error: Option<String>,
+}
+
+impl WorkspaceForm {
+    pub fn show(&mut self) {
+        *self = WorkspaceForm { visible: true, ..Default::default() };
+    }
+
+    pub fn hide(&mut self) {
+        *self = WorkspaceForm::default();
+    }
+}
+
+pub fn view<'a>(workspaces: &'a Workspaces, form: &'a WorkspaceForm, enabled: bool) -> Element<'a, WorkspaceMessage> {
+    if form.visible {
+        let mut content = row![
+            text_input("Workspace name", &form.name)
+                .on_input(WorkspaceMessage::NameChanged)
+                .padding(4)
+                .size(13)
+                .width(Length::Fixed(140.0)),
+            text_input("Data directory (absolute path)", &form.dir)
+                .on_input(WorkspaceMessage::DirChanged)
+                .on_submit(WorkspaceMessage::Add)
+                .padding(4)
+                .size(13)
+                .width(Length::Fixed(260.0)),
+            button(text("Add").size(12)).on_press(WorkspaceMessage::Add).padding(4),
+            button(text("Cancel").size(12)).on_press(WorkspaceMessage::CancelAdd).padding(4),
+        ]
+        .spacing(6)
+        .align_y(Alignment::Center);
+
+        if let Some(error) = &form.error {
+            content = content.push(text(error.as_str()).size(12));
+        }
+        return content.into();
+    }
+
+    let can_remove = enabled && workspaces.active != workspace::DEFAULT;
+
+    row![
+        text("Workspace:").size(13),
+        pick_list(
+            workspaces.names(),
+            Some(workspaces.active.clone()),
+            WorkspaceMessage::Selected,
+        )
+        .text_size(13)
+        .padding(4),
+        button(text("+").size(12))
+            .on_press_maybe(enabled.then_some(WorkspaceMessage::ShowAdd))
+            .padding(4),
+        button(text("Remove").size(12))
+            .on_press_maybe(can_remove.then_some(WorkspaceMessage::RemoveActive))
+            .padding(4),
+    ]
+    .spacing(6)
+    .align_y(Alignment::Center)
+    .into()
+}
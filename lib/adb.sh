@@ -35,6 +35,17 @@ get_device_wifi_path() {
     echo "$target_path"
 }
 
+# Save the device's networks as `cmd wifi list-networks` prints them. Works
+# without root on Android 11+, but only has SSIDs and security types.
+list_android_networks() {
+    local listing
+    listing=$(adb shell cmd wifi list-networks 2>/dev/null)
+    if ! grep -q "^Network Id" <<< "$listing"; then
+        return 1
+    fi
+    printf '%s\n' "$listing" > "$LISTING_FILE"
+}
+
 # -----------------------------------------------------------------------------
 # Pull from Android Device
 # -----------------------------------------------------------------------------
@@ -50,10 +61,19 @@ cmd_pull_android() {
     check_adb
 
     local target_path
-    target_path=$(get_device_wifi_path)
+    # Empty without root; handled below rather than by set -e
+    target_path=$(get_device_wifi_path) || true
     
     if [ -z "$target_path" ]; then
-        exit_with_error "WifiConfigStore.xml not found on device (checked new and old paths)."
+        log_warn "WifiConfigStore.xml not readable on device (no root?), listing saved networks instead."
+        mkdir -p "${BASE_DIR}"
+        if ! list_android_networks; then
+            exit_with_error "WifiConfigStore.xml not found on device (checked new and old paths), and listing networks needs Android 11+."
+        fi
+        local count
+        count=$(grep -cv "^Network Id" "$LISTING_FILE")
+        log_info "Saved $count networks to $LISTING_FILE (SSIDs and security types only, no passwords)"
+        return
     fi
 
     log_info "Pulling from: $target_path"
@@ -69,11 +89,17 @@ cmd_pull_android() {
         log_info "Successfully pulled to $XML_FILE"
         # Clean up
         adb shell "rm \"$temp_remote\""
+        rm -f "$LISTING_FILE"
     else
         adb shell "rm \"$temp_remote\""
         exit_with_error "Failed to pull file via ADB."
     fi
     
+    # The GUI imports with its own preview instead
+    if [ "$NO_IMPORT" = true ]; then
+        return
+    fi
+
     # Auto-trigger import after pull
     log_info "Triggering Import..."
     cmd_import_android
@@ -107,9 +133,7 @@ cmd_push_android() {
     fi
 
     log_warn "This will overwrite WiFi config on the device: $target_path"
-    read -p "Are you sure? [y/N]: " -n 1 -r
-    echo ""
-    if [[ ! $REPLY =~ ^[Yy]$ ]]; then
+    if ! confirm "Overwrite the WiFi config on the device?" "$target_path"; then
         log_info "Aborted."
         return
     fi
@@ -10,6 +10,8 @@ create_nmconnection() {
     local ssid="$1"
     local password="$2"
     local filename="$3"
+    # Security label as shown in the report (defaults to WPA/WPA2 when a password is given)
+    local security="${4:-}"
     local uuid_val
     uuid_val=$(uuidgen)
     local timestamp_val
@@ -21,6 +23,17 @@ create_nmconnection() {
         log_info "  + Creating: $(basename "$filename")"
     fi
 
+    if [ "$password" = "null" ]; then
+        password=""
+    fi
+    if [ -z "$security" ] || [ "$security" = "Unknown" ]; then
+        if [ -n "$password" ]; then
+            security="WPA/WPA2"
+        else
+            security="Open"
+        fi
+    fi
+
     cat <<EOF > "$filename"
 [connection]
 id=$ssid
@@ -36,15 +49,7 @@ hidden=false
 
 EOF
 
-    if [ -n "$password" ] && [ "$password" != "null" ]; then
-        cat <<EOF >> "$filename"
-[wifi-security]
-auth-alg=open
-key-mgmt=wpa-psk
-psk=$password
-
-EOF
-    fi
+    write_security_section "$security" "$password" >> "$filename"
 
     cat <<EOF >> "$filename"
 [ipv4]
@@ -60,6 +65,60 @@ dns-search=
 EOF
 }
 
+# Print the [wifi-security] section for a security label (see Security in the GUI)
+write_security_section() {
+    local security="$1"
+    local password="$2"
+
+    case "$security" in
+        "WPA/WPA2"|"WPA2/WPA3"|"WPA3"|"WEP")
+            # Key-based modes without a key end up open
+            [ -z "$password" ] && return
+            ;;
+        "OWE"|"Enterprise")
+            ;;
+        *)
+            return
+            ;;
+    esac
+
+    echo "[wifi-security]"
+    echo "auth-alg=open"
+    case "$security" in
+        "WPA/WPA2")
+            echo "key-mgmt=wpa-psk"
+            echo "psk=$password"
+            ;;
+        "WPA2/WPA3")
+            echo "key-mgmt=wpa-psk"
+            echo "pmf=2"
+            echo "psk=$password"
+            ;;
+        "WPA3")
+            echo "key-mgmt=sae"
+            echo "psk=$password"
+            ;;
+        "WEP")
+            echo "key-mgmt=none"
+            echo "wep-key0=$password"
+            # Type 1 is a raw 40/104-bit key (5/13 chars or 10/26 hex digits), 2 a passphrase
+            local wep_key_type=2
+            case "${#password}" in
+                5|13) wep_key_type=1 ;;
+                10|26) [[ "$password" =~ ^[0-9A-Fa-f]+$ ]] && wep_key_type=1 ;;
+            esac
+            echo "wep-key-type=$wep_key_type"
+            ;;
+        "OWE")
+            echo "key-mgmt=owe"
+            ;;
+        "Enterprise")
+            echo "key-mgmt=wpa-eap"
+            ;;
+    esac
+    echo ""
+}
+
 # -----------------------------------------------------------------------------
 # Parse nmconnection File
 # -----------------------------------------------------------------------------
@@ -67,27 +126,42 @@ EOF
 parse_nmconnection_file() {
     local filepath="$1"
     # Output: SSID|Security|Password
-    # Use awk to parse ini-like file
-    awk -F "=" '
-        BEGIN { section=""; ssid=""; conn_id=""; psk=""; key_mgmt=""; }
-        /^\[.*\]/ { 
-            section=$0 
+    # Use awk to parse ini-like file; values may themselves contain "="
+    awk '
+        function val() { return substr($0, index($0, "=") + 1) }
+        BEGIN { section=""; ssid=""; conn_id=""; psk=""; key_mgmt=""; proto=""; pairwise=""; pmf=""; wep_key=""; wep_type=""; eap_pass=""; }
+        /^\[.*\]/ {
+            section=$0
             gsub(/[\[\]]/, "", section)
         }
-        /^ssid=/ { if(section=="wifi") ssid=$2 }
-        /^id=/ { if(section=="connection") conn_id=$2 }
-        /^key-mgmt=/ { if(section=="wifi-security") key_mgmt=$2 }
-        /^psk=/ { if(section=="wifi-security") psk=$2 }
-        
+        /^ssid=/ { if(section=="wifi") ssid=val() }
+        /^id=/ { if(section=="connection") conn_id=val() }
+        section=="wifi-security" && /^key-mgmt=/ { key_mgmt=val() }
+        section=="wifi-security" && /^psk=/ { psk=val() }
+        section=="wifi-security" && /^proto=/ { proto=val() }
+        section=="wifi-security" && /^pairwise=/ { pairwise=val() }
+        section=="wifi-security" && /^pmf=/ { pmf=val() }
+        section=="wifi-security" && /^wep-key0=/ { wep_key=val() }
+        section=="wifi-security" && /^wep-key-type=/ { wep_type=val() }
+        section=="802-1x" && /^password=/ { eap_pass=val() }
+
         END {
             if (ssid == "" && conn_id != "") ssid = conn_id
             if (ssid != "") {
-                sec = "Unknown"
-                if (index(key_mgmt, "wpa") > 0) sec = "WPA/WPA2"
-                else if (index(key_mgmt, "sae") > 0) sec = "WPA3"
-                else if (key_mgmt == "" && psk == "") sec = "Open"
-                else if (psk != "") sec = "WPA/WPA2" # Fallback guess
-                
+                # Labels must match Security in gui/src/models/network.rs
+                if (key_mgmt == "sae") sec = "WPA3"
+                else if (key_mgmt == "owe") sec = "OWE"
+                else if (key_mgmt == "wpa-eap" || key_mgmt == "wpa-eap-suite-b-192" || key_mgmt == "ieee8021x") sec = "Enterprise"
+                else if (key_mgmt == "wpa-psk" && pmf == "2" && proto != "wpa" && pairwise != "tkip") sec = "WPA2/WPA3"
+                else if (key_mgmt == "wpa-psk") sec = "WPA/WPA2"
+                else if (key_mgmt == "none" || (key_mgmt == "" && (wep_type != "" || wep_key != ""))) sec = "WEP"
+                else if (key_mgmt == "" && psk != "") sec = "WPA/WPA2"
+                else if (key_mgmt == "") sec = "Open"
+                else sec = "Unknown"
+
+                if (sec == "WEP") psk = wep_key
+                if (sec == "Enterprise") psk = eap_pass
+
                 print ssid "|" sec "|" psk
             }
         }
@@ -100,12 +174,13 @@ parse_nmconnection_file() {
 
 parse_android_xml() {
     local xml_file="$1"
-    # Output: SSID|Password lines
+    # Output: SSID|Password|Kind lines, Kind is "eap" for enterprise networks
+    # and "encrypted" for keys stored as keystore-bound <EncryptedData>
     # Uses awk with record separator to parse Network blocks
     awk '
         BEGIN { RS="</Network>" }
         {
-            ssid=""; psk="null";
+            ssid=""; psk="null"; kind="";
             
             # Extract SSID
             # Look for <string name="SSID">"SomeSSID"</string>
@@ -117,8 +192,12 @@ parse_android_xml() {
             match($0, /<string name="PreSharedKey">([^<]+)<\/string>/, b)
             if (b[1] != "") psk = b[1]
 
+            # EAP settings live in <WifiEnterpriseConfiguration>; -1 means none
+            if (match($0, /<int name="EapMethod" value="([0-9-]+)"/, c) && c[1] != "-1") kind = "eap"
+            if (index($0, "EncryptedData") > 0) kind = "encrypted"
+
             if (ssid != "") {
-                print ssid "|" psk
+                print ssid "|" psk "|" kind
             }
         }
     ' "$xml_file"
@@ -139,7 +218,7 @@ cmd_import_android() {
     local imported_count=0
     local skipped_count=0
 
-    while IFS="|" read -r ssid password; do
+    while IFS="|" read -r ssid password kind; do
         if [ -z "$ssid" ] || [ "$ssid" == "null" ]; then
             continue
         fi
@@ -154,19 +233,28 @@ cmd_import_android() {
 
         if [ -f "$filepath" ]; then
             skipped_count=$((skipped_count + 1))
-            log_debug "Skipping existing: $clean_name"
+            log_network debug skipped "$ssid" "${clean_name}.nmconnection" "Skipping existing: $clean_name"
+        elif [ "$kind" = "encrypted" ]; then
+            # Only the device can decrypt the key; an open profile would be wrong
+            skipped_count=$((skipped_count + 1))
+            log_network warn skipped "$ssid" "${clean_name}.nmconnection" "  ! Skipping, credentials unavailable (encrypted on the device): $clean_name"
+        elif [ "$kind" = "eap" ]; then
+            # Without its [802-1x] settings the profile would connect as an open network
+            skipped_count=$((skipped_count + 1))
+            log_network warn skipped "$ssid" "${clean_name}.nmconnection" "  ! Skipping enterprise network (import it from the GUI): $clean_name"
         else
             if [ "$DRY_RUN" = false ]; then
                 create_nmconnection "$ssid" "$password" "$filepath"
+                log_network info created "$ssid" "${clean_name}.nmconnection" "  + Created: $clean_name"
             else
-                log_info "  + [Dry Run] Would create: $clean_name"
+                log_network info created "$ssid" "${clean_name}.nmconnection" "  + [Dry Run] Would create: $clean_name"
             fi
             imported_count=$((imported_count + 1))
         fi
 
     done < <(parse_android_xml "$XML_FILE")
 
-    log_info "Imported: $imported_count, Skipped: $skipped_count"
+    log_counts "Imported: $imported_count, Skipped: $skipped_count" imported "$imported_count" skipped "$skipped_count"
     
     sync_report
 }
@@ -175,6 +263,7 @@ cmd_import_android() {
 # Install to System
 # -----------------------------------------------------------------------------
 
+# With filenames, only those local files are installed (the GUI's selected plan items)
 cmd_install() {
     log_info "Starting System Installation..."
     
@@ -195,8 +284,8 @@ cmd_install() {
         return
     fi
     
-    local installed_count=0
-    local updated_count=0
+    INSTALL_NEW=()
+    INSTALL_UPDATED=()
 
     # Enable nullglob to handle case where no files exist
     shopt -s nullglob
@@ -205,6 +294,10 @@ cmd_install() {
         filename=$(basename "$local_path")
         local system_path="$SYSTEM_DIR/$filename"
 
+        if [ $# -gt 0 ] && ! in_list "$filename" "$@"; then
+            continue
+        fi
+
         local do_install=false
         local do_update=false
 
@@ -222,38 +315,156 @@ cmd_install() {
         fi
 
         if [ "$do_install" = true ]; then
-            log_info "  + Install: $filename"
-            installed_count=$((installed_count + 1))
-            if [ "$DRY_RUN" = false ]; then
-                cp "$local_path" "$system_path"
-                chmod 600 "$system_path"
-                chown root:root "$system_path"
-            fi
+            INSTALL_NEW+=("$filename")
         elif [ "$do_update" = true ]; then
-            log_info "  ~ Update: $filename"
-            updated_count=$((updated_count + 1))
-            if [ "$DRY_RUN" = false ]; then
-                cp "$local_path" "$system_path"
-                chmod 600 "$system_path"
-                chown root:root "$system_path"
-            fi
+            INSTALL_UPDATED+=("$filename")
         fi
     done
     shopt -u nullglob
 
-    if [ "$DRY_RUN" = false ] && { [ $installed_count -gt 0 ] || [ $updated_count -gt 0 ]; }; then
-        log_info "Reloading NetworkManager..."
-        nmcli connection reload
+    local installed_count=${#INSTALL_NEW[@]}
+    local updated_count=${#INSTALL_UPDATED[@]}
+
+    if [ "$DRY_RUN" = false ] && [ $((installed_count + updated_count)) -gt 0 ]; then
+        apply_install
     fi
 
-    log_info "Installed: $installed_count, Updated: $updated_count"
+    # Reported only once the whole install went through
+    for filename in ${INSTALL_NEW[@]+"${INSTALL_NEW[@]}"}; do
+        log_network info installed "" "$filename" "  + Install: $filename"
+    done
+    for filename in ${INSTALL_UPDATED[@]+"${INSTALL_UPDATED[@]}"}; do
+        log_network info updated "" "$filename" "  ~ Update: $filename"
+    done
+
+    log_counts "Installed: $installed_count, Updated: $updated_count" installed "$installed_count" updated "$updated_count"
     sync_report
 }
 
+# -----------------------------------------------------------------------------
+# Install Transaction
+# -----------------------------------------------------------------------------
+
+# Files of the running install: new ones and ones that replace a system file
+INSTALL_NEW=()
+INSTALL_UPDATED=()
+# Copies of the replaced system files, restored by rollback_install
+INSTALL_SNAPSHOT=""
+
+# Copy INSTALL_NEW and INSTALL_UPDATED from LOCAL_DIR to SYSTEM_DIR as one
+# transaction: snapshot the files that will be replaced, write each one
+# atomically, reload NetworkManager and check it loaded every file. Any failure
+# (or an interruption) restores the snapshot and exits with an error.
+apply_install() {
+    local filename
+    local files=(${INSTALL_NEW[@]+"${INSTALL_NEW[@]}"} ${INSTALL_UPDATED[@]+"${INSTALL_UPDATED[@]}"})
+
+    INSTALL_SNAPSHOT=$(mktemp -d "${TMPDIR:-/tmp}/wifi-manager-snapshot.XXXXXX") \
+        || exit_with_error "Failed to create a snapshot directory; nothing was changed"
+
+    for filename in ${INSTALL_UPDATED[@]+"${INSTALL_UPDATED[@]}"}; do
+        if ! cp -p "$SYSTEM_DIR/$filename" "$INSTALL_SNAPSHOT/$filename"; then
+            rm -rf "$INSTALL_SNAPSHOT"
+            exit_with_error "Failed to snapshot $filename; nothing was changed"
+        fi
+    done
+    log_debug "Snapshot of ${#INSTALL_UPDATED[@]} system files: $INSTALL_SNAPSHOT"
+
+    trap 'rollback_install "Install interrupted"' INT TERM
+
+    for filename in "${files[@]}"; do
+        if ! write_system_file "$LOCAL_DIR/$filename" "$SYSTEM_DIR/$filename"; then
+            rollback_install "Failed to write $filename"
+        fi
+    done
+
+    log_info "Reloading NetworkManager..."
+    if ! nmcli connection reload; then
+        rollback_install "NetworkManager failed to reload its connections"
+    fi
+
+    local missing
+    missing=$(unloaded_connections "${files[@]}")
+    if [ -n "$missing" ]; then
+        rollback_install "NetworkManager did not load: $missing"
+    fi
+
+    trap - INT TERM
+    rm -rf "$INSTALL_SNAPSHOT"
+}
+
+# Put back the snapshot of the running install, remove the files it added and exit
+rollback_install() {
+    local filename kept=false
+    trap - INT TERM
+
+    log_error "$1"
+    log_warn "Rolling back the install..."
+
+    for filename in ${INSTALL_NEW[@]+"${INSTALL_NEW[@]}"}; do
+        rm -f "$SYSTEM_DIR/$filename" || log_error "Failed to remove $filename"
+    done
+    for filename in ${INSTALL_UPDATED[@]+"${INSTALL_UPDATED[@]}"}; do
+        if ! restore_system_file "$INSTALL_SNAPSHOT/$filename" "$SYSTEM_DIR/$filename"; then
+            log_error "Failed to restore $filename"
+            kept=true
+        fi
+    done
+
+    nmcli connection reload || log_warn "NetworkManager failed to reload the restored connections"
+
+    if [ "$kept" = true ]; then
+        exit_with_error "Rollback incomplete; the original files are kept in $INSTALL_SNAPSHOT"
+    fi
+    rm -rf "$INSTALL_SNAPSHOT"
+    exit_with_error "Install rolled back; the system connections are unchanged"
+}
+
+# Replace a system file in one rename, so NetworkManager never sees it half-written
+# (the temporary name starts with a dot, which NetworkManager ignores)
+write_system_file() {
+    local source="$1" target="$2"
+    local tmp
+    tmp="$(dirname "$target")/.$(basename "$target").tmp"
+
+    if cp "$source" "$tmp" && chmod 600 "$tmp" && chown root:root "$tmp" && mv -f "$tmp" "$target"; then
+        return 0
+    fi
+    rm -f "$tmp"
+    return 1
+}
+
+# Same as write_system_file for a snapshot copy, keeping its mode and owner
+restore_system_file() {
+    local source="$1" target="$2"
+    local tmp
+    tmp="$(dirname "$target")/.$(basename "$target").tmp"
+
+    if cp -p "$source" "$tmp" && mv -f "$tmp" "$target"; then
+        return 0
+    fi
+    rm -f "$tmp"
+    return 1
+}
+
+# Print which of the given system files NetworkManager has no connection for
+unloaded_connections() {
+    local loaded filename missing=()
+    loaded=$(nmcli -g FILENAME connection show 2>/dev/null || true)
+
+    for filename in "$@"; do
+        if ! grep -Fxq "$SYSTEM_DIR/$filename" <<< "$loaded"; then
+            missing+=("$filename")
+        fi
+    done
+    echo "${missing[*]}"
+}
+
 # -----------------------------------------------------------------------------
 # Backup from System
 # -----------------------------------------------------------------------------
 
+# With filenames, only those system files are backed up
 cmd_backup() {
     log_info "Starting System Backup..."
     
@@ -282,6 +493,10 @@ cmd_backup() {
         filename=$(basename "$system_path")
         local local_path="$LOCAL_DIR/$filename"
 
+        if [ $# -gt 0 ] && ! in_list "$filename" "$@"; then
+            continue
+        fi
+
         local do_pull=false
         local do_update=false
 
@@ -298,7 +513,7 @@ cmd_backup() {
         fi
 
         if [ "$do_pull" = true ]; then
-            log_info "  + Pull: $filename"
+            log_network info pulled "" "$filename" "  + Pull: $filename"
             pulled_count=$((pulled_count + 1))
             if [ "$DRY_RUN" = false ]; then
                 cp "$system_path" "$local_path"
@@ -308,7 +523,7 @@ cmd_backup() {
                 fi
             fi
         elif [ "$do_update" = true ]; then
-            log_info "  ~ Update: $filename"
+            log_network info updated "" "$filename" "  ~ Update: $filename"
             updated_count=$((updated_count + 1))
             if [ "$DRY_RUN" = false ]; then
                 cp "$system_path" "$local_path"
@@ -321,6 +536,45 @@ cmd_backup() {
     done
     shopt -u nullglob
 
-    log_info "Pulled: $pulled_count, Updated: $updated_count"
+    log_counts "Pulled: $pulled_count, Updated: $updated_count" pulled "$pulled_count" updated "$updated_count"
     sync_report
 }
+
+# -----------------------------------------------------------------------------
+# Show System Connections
+# -----------------------------------------------------------------------------
+
+# Start of each file in show-system output: @@WIFI_MANAGER_FILE@@<TAB><filename>
+FILE_MARKER="@@WIFI_MANAGER_FILE@@"
+
+# Print system connection files (root-only) so they can be compared with the
+# local ones; all of them without arguments. Files that are not installed are left out.
+cmd_show_system() {
+    local filenames=("$@")
+    if [ ${#filenames[@]} -eq 0 ]; then
+        local system_path
+        shopt -s nullglob
+        for system_path in "$SYSTEM_DIR"/*.nmconnection; do
+            filenames+=("$(basename "$system_path")")
+        done
+        shopt -u nullglob
+    fi
+
+    local filename
+    for filename in ${filenames[@]+"${filenames[@]}"}; do
+        # Only plain connection filenames, never other files readable by root
+        if [[ "$filename" == */* ]] || [[ "$filename" != *.nmconnection ]]; then
+            exit_with_error "Not a connection filename: $filename"
+        fi
+
+        local system_path="$SYSTEM_DIR/$filename"
+        if [ ! -f "$system_path" ]; then
+            log_debug "Not installed: $filename"
+            continue
+        fi
+
+        printf '%s\t%s\n' "$FILE_MARKER" "$filename"
+        # Always end with a newline so the next marker starts its own line
+        printf '%s\n' "$(cat "$system_path")"
+    done
+}
@@ -13,45 +13,72 @@ sync_report_to_connections() {
     local report_data=""
     if [ -f "$REPORT_FILE" ]; then
         # Parse table: | Network Name | Security Type | Password | ...
-        # Skip header/separator lines
-        report_data=$(awk -F "|" '
+        # Skip header/separator lines. Cells are split on "|" but not "\|", and
+        # printed separated by \x1f so SSIDs and passwords may contain "|".
+        report_data=$(awk '
+            # Split a table row into cells, keeping escapes for unescape()
+            function split_row(line, cells,    n, i, c, cell) {
+                n = 0; cell = ""
+                for (i = 2; i <= length(line); i++) {
+                    c = substr(line, i, 1)
+                    if (c == "\\") { cell = cell c substr(line, i + 1, 1); i++ }
+                    else if (c == "|") { cells[++n] = cell; cell = "" }
+                    else cell = cell c
+                }
+                return n
+            }
+            # Unescape: \| -> |, \\ -> \
+            function unescape(s,    out, i, c, next_c) {
+                out = ""
+                for (i = 1; i <= length(s); i++) {
+                    c = substr(s, i, 1); next_c = substr(s, i + 1, 1)
+                    if (c == "\\" && (next_c == "|" || next_c == "\\")) { out = out next_c; i++ }
+                    else out = out c
+                }
+                return out
+            }
+            function trim(s) { gsub(/^ +| +$/, "", s); return s }
             /^\|.*\|/ {
                 if ($0 ~ /^\| Network Name/) next
                 if ($0 ~ /^\|--/) next
                 
-                # Strip whitespace
-                ssid = $2; gsub(/^ +| +$/, "", ssid)
-                # Unescape: \| -> |, \\ -> \
-                gsub(/\\\|/, "|", ssid)
-                gsub(/\\\\/, "\\", ssid)
+                split("", cells)
+                split_row($0, cells)
+                ssid = unescape(trim(cells[1]))
+                sec = trim(cells[2])
                 
-                sec = $3; gsub(/^ +| +$/, "", sec)
-                
-                pass = $4; gsub(/^ +| +$/, "", pass)
+                pass = trim(cells[3])
                 # Remove backticks `pass`
                 gsub(/^`|`$/, "", pass)
                 if (pass == "N/A") pass = ""
+                pass = unescape(pass)
                 
-                print ssid "|" sec "|" pass
+                print ssid "\037" sec "\037" pass
             }
         ' "$REPORT_FILE")
     fi
 
     # Iterate Report Items and Create Missing Connections
     if [ -n "$report_data" ]; then
-        while IFS="|" read -r r_ssid r_sec r_pass; do
+        while IFS=$'\x1f' read -r r_ssid r_sec r_pass; do
             [ -z "$r_ssid" ] && continue
 
             local clean_name
             clean_name=$(sanitize_filename "$r_ssid")
             local filepath="${LOCAL_DIR}/${clean_name}.nmconnection"
 
-            if [ ! -f "$filepath" ]; then
+            if [ "$r_sec" = "Enterprise" ]; then
+                # The report has no 802.1X settings (EAP method, identity, certificates)
+                if [ ! -f "$filepath" ]; then
+                    log_network warn skipped "$r_ssid" "${clean_name}.nmconnection" "  ! Cannot recreate enterprise network from report: $clean_name"
+                fi
+            elif [ ! -f "$filepath" ]; then
                 # Create from report
                 if [ "$DRY_RUN" = false ]; then
-                    create_nmconnection "$r_ssid" "$r_pass" "$filepath"
+                    create_nmconnection "$r_ssid" "$r_pass" "$filepath" "$r_sec"
+                    log_network info created "$r_ssid" "${clean_name}.nmconnection" "  + Created from report: $clean_name"
                 else
-                    log_info "  + [Dry Run] Would create from report: $clean_name"
+                    log_network info created "$r_ssid" "${clean_name}.nmconnection" "  + [Dry Run] Would create from report: $clean_name"
                 fi
             else
                 # File exists, check if we need to inject password (if missing in file but present in report)
@@ -61,9 +88,9 @@ sync_report_to_connections() {
                 
                 if [ -z "$f_pass" ] && [ -n "$r_pass" ]; then
                     if [ "$DRY_RUN" = false ]; then
-                        log_info "  ~ Inject password from report: $clean_name"
+                        log_network info updated "$r_ssid" "${clean_name}.nmconnection" "  ~ Inject password from report: $clean_name"
                         # Re-create file to inject password (simple overwrite)
-                        create_nmconnection "$r_ssid" "$r_pass" "$filepath"
+                        create_nmconnection "$r_ssid" "$r_pass" "$filepath" "$r_sec"
                     fi
                 fi
             fi
@@ -107,11 +134,11 @@ generate_report() {
             echo "|--------------|---------------|----------|--------|"
 
             while IFS="|" read -r ssid sec pass; do
-                # Escape pipes in SSID
-                safe_ssid=$(echo "$ssid" | sed 's/\\/\\\\/g' | sed 's/|/\\|/g')
+                # Escape pipes in SSID and password
+                safe_ssid=$(escape_report_cell "$ssid")
                 safe_pass="N/A"
                 if [ -n "$pass" ]; then
-                    safe_pass="\`$pass\`"
+                    safe_pass="\`$(escape_report_cell "$pass")\`"
                 fi
                 # Get actual network status
                 status=$(get_network_status "$ssid")
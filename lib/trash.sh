@@ -0,0 +1,170 @@
+#!/usr/bin/env bash
+# WiFi Manager - Delete, Forget & Trash Operations
+# Sourced by wifi-manager.sh
+
+# -----------------------------------------------------------------------------
+# Report Row Removal
+# -----------------------------------------------------------------------------
+
+remove_report_row() {
+    local ssid="$1"
+
+    if [ ! -f "$REPORT_FILE" ]; then
+        return
+    fi
+
+    # Escape the same way generate_report does
+    local safe_ssid
+    safe_ssid=$(escape_report_cell "$ssid")
+
+    local temp_report
+    temp_report=$(mktemp)
+    # ENVIRON avoids awk -v backslash processing
+    ROW_PREFIX="| $safe_ssid |" awk 'index($0, ENVIRON["ROW_PREFIX"]) != 1' "$REPORT_FILE" > "$temp_report"
+    cat "$temp_report" > "$REPORT_FILE"
+    rm -f "$temp_report"
+}
+
+# -----------------------------------------------------------------------------
+# Delete (move to trash)
+# -----------------------------------------------------------------------------
+
+cmd_delete() {
+    if [ $# -eq 0 ]; then
+        exit_with_error "Usage: delete <ssid>... [--forget]"
+    fi
+
+    log_info "Deleting networks..."
+
+    local deleted_count=0
+    local forget_names=()
+
+    for ssid in "$@"; do
+        local clean_name
+        clean_name=$(sanitize_filename "$ssid")
+        local filepath="${LOCAL_DIR}/${clean_name}.nmconnection"
+
+        if [ ! -f "$filepath" ]; then
+            log_network warn skipped "$ssid" "${clean_name}.nmconnection" "  ! Not found: ${clean_name}.nmconnection"
+            continue
+        fi
+
+        local trash_id
+        # Nanoseconds keep two deletes of the same name within a second apart
+        trash_id="$(date +%s.%N)-${clean_name}"
+
+        if [ "$DRY_RUN" = false ]; then
+            mkdir -p "$TRASH_DIR"
+            mv "$filepath" "${TRASH_DIR}/${trash_id}.nmconnection"
+            {
+                echo "[trash]"
+                echo "ssid=$ssid"
+                echo "filename=${clean_name}.nmconnection"
+                echo "deleted=$(date +%s)"
+                echo "forgotten=$FORGET"
+            } > "${TRASH_DIR}/${trash_id}.meta"
+            remove_report_row "$ssid"
+            log_network info trashed "$ssid" "${clean_name}.nmconnection" "  - Trashed: ${clean_name}.nmconnection"
+        else
+            log_network info trashed "$ssid" "${clean_name}.nmconnection" "  - [Dry Run] Would trash: ${clean_name}.nmconnection"
+        fi
+
+        deleted_count=$((deleted_count + 1))
+        forget_names+=("$ssid")
+    done
+
+    log_counts "Deleted: $deleted_count (restore with: restore <id>, see ${TRASH_DIR})" deleted "$deleted_count"
+
+    if [ "$FORGET" = true ] && [ ${#forget_names[@]} -gt 0 ]; then
+        cmd_forget "${forget_names[@]}"
+    fi
+}
+
+# -----------------------------------------------------------------------------
+# Forget from System
+# -----------------------------------------------------------------------------
+
+cmd_forget() {
+    if [ $# -eq 0 ]; then
+        exit_with_error "Usage: forget <ssid>..."
+    fi
+
+    log_info "Forgetting networks from system..."
+
+    if [ "$EUID" -ne 0 ] && [ "$DRY_RUN" = false ]; then
+        exit_with_error "This command requires root privileges (use sudo)."
+    fi
+
+    local forgotten_count=0
+
+    for ssid in "$@"; do
+        local clean_name
+        clean_name=$(sanitize_filename "$ssid")
+        local system_path="${SYSTEM_DIR}/${clean_name}.nmconnection"
+
+        if [ ! -f "$system_path" ]; then
+            log_debug "Not installed: ${clean_name}.nmconnection"
+            continue
+        fi
+
+        log_network info forgotten "$ssid" "${clean_name}.nmconnection" "  - Forget: ${clean_name}.nmconnection"
+        forgotten_count=$((forgotten_count + 1))
+        if [ "$DRY_RUN" = false ]; then
+            rm -f "$system_path"
+        fi
+    done
+
+    if [ "$DRY_RUN" = false ] && [ $forgotten_count -gt 0 ]; then
+        log_info "Reloading NetworkManager..."
+        nmcli connection reload
+    fi
+
+    log_counts "Forgotten: $forgotten_count" forgotten "$forgotten_count"
+}
+
+# -----------------------------------------------------------------------------
+# Restore from Trash
+# -----------------------------------------------------------------------------
+
+cmd_restore() {
+    if [ $# -eq 0 ]; then
+        exit_with_error "Usage: restore <trash-id>..."
+    fi
+
+    ensure_local_dir
+
+    local restored_count=0
+
+    for trash_id in "$@"; do
+        local trashed="${TRASH_DIR}/${trash_id}.nmconnection"
+        local meta="${TRASH_DIR}/${trash_id}.meta"
+
+        if [ ! -f "$trashed" ]; then
+            log_warn "  ! Not in trash: $trash_id"
+            continue
+        fi
+
+        local filename
+        filename=$(awk -F "=" '/^filename=/ { sub(/^filename=/, ""); print; exit }' "$meta" 2>/dev/null)
+        if [ -z "$filename" ]; then
+            filename="${trash_id#*-}.nmconnection"
+        fi
+
+        if [ -f "${LOCAL_DIR}/${filename}" ]; then
+            log_network warn skipped "" "$filename" "  ! Already exists, not restoring: $filename"
+            continue
+        fi
+
+        if [ "$DRY_RUN" = false ]; then
+            mv "$trashed" "${LOCAL_DIR}/${filename}"
+            rm -f "$meta"
+            log_network info restored "" "$filename" "  + Restored: $filename"
+        else
+            log_network info restored "" "$filename" "  + [Dry Run] Would restore: $filename"
+        fi
+        restored_count=$((restored_count + 1))
+    done
+
+    log_counts "Restored: $restored_count" restored "$restored_count"
+    sync_report
+}
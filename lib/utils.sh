@@ -24,29 +24,145 @@ fi
 # Logging Functions
 # -----------------------------------------------------------------------------
 
+# With --json every message is printed as one JSON event per line instead
+# (debug messages included, whatever VERBOSE says)
+
 log_info() {
+    if [[ "${JSON_OUTPUT:-false}" == "true" ]]; then
+        json_event info "$1"
+        return
+    fi
     echo -e "${GREEN}[INFO]${NC} $1"
 }
 
 log_warn() {
+    if [[ "${JSON_OUTPUT:-false}" == "true" ]]; then
+        json_event warn "$1"
+        return
+    fi
     echo -e "${YELLOW}[WARN]${NC} $1"
 }
 
 log_error() {
+    if [[ "${JSON_OUTPUT:-false}" == "true" ]]; then
+        json_event error "$1" >&2
+        return
+    fi
     echo -e "${RED}[ERROR]${NC} $1" >&2
 }
 
 log_debug() {
+    if [[ "${JSON_OUTPUT:-false}" == "true" ]]; then
+        json_event debug "$1"
+        return
+    fi
     if [[ "${VERBOSE:-false}" == "true" ]]; then
         echo -e "${BLUE}[DEBUG]${NC} $1"
     fi
 }
 
+# What happened to one network: log_network <level> <action> <ssid> <file> <message>
+# (ssid or file may be empty)
+log_network() {
+    local level="$1"
+    local action="$2"
+    local ssid="$3"
+    local file="$4"
+    local message="$5"
+
+    if [[ "${JSON_OUTPUT:-false}" == "true" ]]; then
+        json_event "$level" "$message" action "$action" ssid "$ssid" file "$file"
+        return
+    fi
+    case "$level" in
+        debug) log_debug "$message" ;;
+        warn) log_warn "$message" ;;
+        *) log_info "$message" ;;
+    esac
+}
+
+# Final counters of a command: log_counts <message> [<name> <count>]...
+log_counts() {
+    local message="$1"
+    shift
+
+    if [[ "${JSON_OUTPUT:-false}" != "true" ]]; then
+        log_info "$message"
+        return
+    fi
+
+    local counts=""
+    while [ $# -ge 2 ]; do
+        counts+="${counts:+,}\"$(json_escape "$1")\":$2"
+        shift 2
+    done
+    printf '%s,"counts":{%s}}\n' "$(json_head info "$message")" "$counts"
+}
+
+# -----------------------------------------------------------------------------
+# JSON Events (--json)
+# -----------------------------------------------------------------------------
+
+json_escape() {
+    local s="$1"
+    s="${s//\\/\\\\}"
+    s="${s//\"/\\\"}"
+    s="${s//$'\n'/\\n}"
+    s="${s//$'\r'/\\r}"
+    s="${s//$'\t'/\\t}"
+    printf '%s' "$s"
+}
+
+# Opening fields shared by all events (without the closing brace)
+json_head() {
+    printf '{"level":"%s","operation":"%s","dry_run":%s,"message":"%s"' \
+        "$1" "$(json_escape "${OPERATION:-}")" "${DRY_RUN:-false}" "$(json_escape "$2")"
+}
+
+# One event line: json_event <level> <message> [<key> <value>]... (empty values are left out)
+json_event() {
+    local line
+    line=$(json_head "$1" "$2")
+    shift 2
+
+    while [ $# -ge 2 ]; do
+        if [ -n "$2" ]; then
+            line+=",\"$1\":\"$(json_escape "$2")\""
+        fi
+        shift 2
+    done
+    printf '%s}\n' "$line"
+}
+
 exit_with_error() {
     log_error "$1"
     exit 1
 }
 
+# -----------------------------------------------------------------------------
+# User Prompts
+# -----------------------------------------------------------------------------
+
+# Ask a yes/no question, returns 0 on yes.
+# With WIFI_MANAGER_PROMPTS=marker (set by the GUI) the question is written to
+# stdout as one marker line and the answer is read as a line from stdin:
+#   @@WIFI_MANAGER_PROMPT@@<TAB>confirm<TAB><question><TAB><detail>
+confirm() {
+    local question="$1"
+    local detail="${2:-}"
+    local reply=""
+
+    if [[ "${WIFI_MANAGER_PROMPTS:-}" == "marker" ]]; then
+        printf '@@WIFI_MANAGER_PROMPT@@\tconfirm\t%s\t%s\n' "$question" "$detail"
+        read -r reply || reply=""
+    else
+        read -p "$question [y/N]: " -n 1 -r reply
+        echo ""
+    fi
+
+    [[ $reply =~ ^[Yy]$ ]]
+}
+
 # -----------------------------------------------------------------------------
 # Dependency Checking
 # -----------------------------------------------------------------------------
@@ -80,6 +196,16 @@ check_nmcli() {
 # String/Filename Utilities
 # -----------------------------------------------------------------------------
 
+# True when the first argument equals one of the others
+in_list() {
+    local needle="$1" item
+    shift
+    for item in "$@"; do
+        [ "$item" = "$needle" ] && return 0
+    done
+    return 1
+}
+
 sanitize_filename() {
     local name="$1"
     # Match Python logic:
@@ -88,6 +214,11 @@ sanitize_filename() {
     echo "$name" | sed 's/[<>:"/\\|?*]/_/g' | sed -e 's/^[ .]*//' -e 's/[ .]*$//'
 }
 
+# Escape "\" and "|" for a table cell of the report
+escape_report_cell() {
+    printf '%s\n' "$1" | sed -e 's/\\/\\\\/g' -e 's/|/\\|/g'
+}
+
 decode_xml_entities() {
     local text="$1"
     # Decode common XML entities
@@ -149,10 +280,14 @@ resolve_paths() {
     LOCAL_DIR="${BASE_DIR}/${LOCAL_DIR_NAME}"
     XML_FILE="${BASE_DIR}/${XML_FILENAME}"
     REPORT_FILE="${BASE_DIR}/${REPORT_FILENAME}"
+    TRASH_DIR="${BASE_DIR}/.trash"
+    # Saved networks listed without root, when the XML cannot be pulled
+    LISTING_FILE="${BASE_DIR}/android_networks.txt"
     
     log_debug "LOCAL_DIR: $LOCAL_DIR"
     log_debug "XML_FILE: $XML_FILE"
     log_debug "REPORT_FILE: $REPORT_FILE"
+    log_debug "TRASH_DIR: $TRASH_DIR"
 }
 
 ensure_local_dir() {
@@ -8,14 +8,21 @@
 #
 # COMMANDS:
 #   import-android   Import networks from Android XML file (data/WifiConfigStore.xml)
-#   pull-android     Pull WiFi config from Android device via ADB (requires root)
+#   pull-android     Pull WiFi config from Android device via ADB (passwords require root)
 #   push-android     Push WiFi config to Android device via ADB (requires root)
 #   sync-local       Bidirectional sync: Report <-> Local connection files
-#   install          Install local connections to system (requires sudo)
-#   backup           Backup system connections to local directory (requires sudo)
+#   install [FILE...]  Install local connections to system (requires sudo)
+#   backup [FILE...]   Backup system connections to local directory (requires sudo)
+#   delete SSID...   Move local connections to the trash (data/.trash)
+#   forget SSID...   Remove connections from the system directory (requires sudo)
+#   restore ID...    Restore trashed connections
+#   show-system [FILE...]  Print system connection files (requires sudo)
 #
 # OPTIONS:
 #   --dry-run        Preview changes without making any modifications
+#   --forget         With delete: also forget the networks from the system
+#   --no-import      With pull-android: only pull the XML, do not import it
+#   --json           Print one JSON event per line instead of log messages
 #   --help           Show this help message
 #
 # EXAMPLES:
@@ -38,9 +45,22 @@ load_config() {
     # Default config in script directory
     local default_config="${SCRIPT_DIR}/config.env"
     
-    # User config location
-    local user_config="${HOME}/.config/wifi-manager/config.env"
+    # User config location (of the invoking user when run via sudo/pkexec,
+    # so privileged runs see the same directories as the GUI)
+    local user_home="${HOME:-}"
+    local invoking_user="${SUDO_USER:-}"
+    if [ -z "$invoking_user" ] && [ -n "${PKEXEC_UID:-}" ]; then
+        invoking_user=$(id -nu "$PKEXEC_UID" 2>/dev/null || true)
+    fi
+    if [ -n "$invoking_user" ]; then
+        user_home=$(getent passwd "$invoking_user" 2>/dev/null | cut -d: -f6)
+        user_home="${user_home:-${HOME:-}}"
+    fi
+    local user_config="${user_home}/.config/wifi-manager/config.env"
     
+    # An exported base (e.g. the GUI's active workspace) wins over both files
+    local env_base="${WIFI_MANAGER_BASE:-}"
+
     # Load default config first
     if [ -f "$default_config" ]; then
         # shellcheck source=config.env
@@ -54,7 +74,7 @@ load_config() {
     fi
     
     # Set BASE_DIR (allow env override)
-    BASE_DIR="${WIFI_MANAGER_BASE:-data}"
+    BASE_DIR="${env_base:-${WIFI_MANAGER_BASE:-data}}"
     
     # Convert relative BASE_DIR to absolute
     if [[ "$BASE_DIR" != /* ]]; then
@@ -79,6 +99,7 @@ source_libraries() {
     source "${lib_dir}/sync.sh"
     source "${lib_dir}/nmconnection.sh"
     source "${lib_dir}/adb.sh"
+    source "${lib_dir}/trash.sh"
 }
 
 # -----------------------------------------------------------------------------
@@ -86,6 +107,10 @@ source_libraries() {
 # -----------------------------------------------------------------------------
 
 DRY_RUN=false
+FORGET=false
+NO_IMPORT=false
+JSON_OUTPUT=false
+OPERATION=""
 
 # -----------------------------------------------------------------------------
 # Help
@@ -100,14 +125,21 @@ USAGE:
 
 COMMANDS:
     import-android   Import networks from Android XML file (data/WifiConfigStore.xml)
-    pull-android     Pull WiFi config from Android device via ADB (requires root)
+    pull-android     Pull WiFi config from Android device via ADB (passwords require root)
     push-android     Push WiFi config to Android device via ADB (requires root)
     sync-local       Bidirectional sync: Report <-> Local connection files
-    install          Install local connections to system (requires sudo)
-    backup           Backup system connections to local directory (requires sudo)
+    install [FILE...]  Install local connections to system (requires sudo)
+    backup [FILE...]   Backup system connections to local directory (requires sudo)
+    delete SSID...   Move local connections to the trash (data/.trash)
+    forget SSID...   Remove connections from the system directory (requires sudo)
+    restore ID...    Restore trashed connections
+    show-system [FILE...]  Print system connection files (requires sudo)
 
 OPTIONS:
     --dry-run        Preview changes without making any modifications
+    --forget         With delete: also forget the networks from the system
+    --no-import      With pull-android: only pull the XML, do not import it
+    --json           Print one JSON event per line instead of log messages
     --help           Show this help message
 
 EXAMPLES:
@@ -123,6 +155,9 @@ EXAMPLES:
     # Pull from Android device and import
     ./wifi-manager.sh pull-android
 
+    # Delete a network locally and from the system
+    sudo ./wifi-manager.sh delete "Home_Network" --forget
+
 CONFIGURATION:
     Default config:  ./config.env
     User override:   ~/.config/wifi-manager/config.env
@@ -152,6 +187,7 @@ main() {
     fi
 
     local command=""
+    local args=()
     
     while [[ $# -gt 0 ]]; do
         case "$1" in
@@ -159,8 +195,24 @@ main() {
                 DRY_RUN=true
                 shift
                 ;;
-            import-android|install|backup|sync-local|pull-android|push-android)
-                command="$1"
+            --forget)
+                FORGET=true
+                shift
+                ;;
+            --no-import)
+                NO_IMPORT=true
+                shift
+                ;;
+            --json)
+                JSON_OUTPUT=true
+                shift
+                ;;
+            import-android|install|backup|sync-local|pull-android|push-android|delete|forget|restore|show-system)
+                if [ -n "$command" ]; then
+                    args+=("$1")
+                else
+                    command="$1"
+                fi
                 shift
                 ;;
             --help|-h)
@@ -168,6 +220,12 @@ main() {
                 exit 0
                 ;;
             *)
+                # Positional names and filenames
+                if [[ "$command" =~ ^(install|backup|delete|forget|restore|show-system)$ ]] && [[ "$1" != --* ]]; then
+                    args+=("$1")
+                    shift
+                    continue
+                fi
                 echo "Unknown argument: $1" >&2
                 echo "Run with --help for usage information." >&2
                 exit 1
@@ -181,15 +239,16 @@ main() {
     fi
 
     # Dispatch command
+    OPERATION="$command"
     case "$command" in
         import-android)
             cmd_import_android
             ;;
         install)
-            cmd_install
+            cmd_install ${args[@]+"${args[@]}"}
             ;;
         backup)
-            cmd_backup
+            cmd_backup ${args[@]+"${args[@]}"}
             ;;
         sync-local)
             sync_report
@@ -200,6 +259,18 @@ main() {
         push-android)
             cmd_push_android
             ;;
+        delete)
+            cmd_delete ${args[@]+"${args[@]}"}
+            ;;
+        forget)
+            cmd_forget ${args[@]+"${args[@]}"}
+            ;;
+        restore)
+            cmd_restore ${args[@]+"${args[@]}"}
+            ;;
+        show-system)
+            cmd_show_system ${args[@]+"${args[@]}"}
+            ;;
     esac
 }
 